@@ -0,0 +1,180 @@
+use super::*;
+
+// the struct's lifetime, if any, is the lifetime of the decoded data
+pub(crate) fn derive_borrow_impl(data: &DeriveInput) -> Result<TokenStream> {
+    let name = &data.ident;
+    let vis = &data.vis;
+    let seed_name = format_ident!("__{}BorrowSeed", name);
+    let fields: Vec<&Field> = match &data.data {
+        Data::Struct(DataStruct {
+            fields: Fields::Named(d),
+            ..
+        }) => d.named.iter().collect(),
+        _ => {
+            return Err(Error::new(
+                data.span(),
+                "only structs with named fields are supported",
+            ))
+        }
+    };
+
+    let mut generics = data.generics.clone();
+    let de: Lifetime = {
+        let mut lifetimes = data.generics.lifetimes();
+        match (lifetimes.next(), lifetimes.next()) {
+            (Some(l), None) => l.lifetime.clone(),
+            (None, _) => {
+                generics.params.insert(0, parse_quote!('_de));
+                parse_quote!('_de)
+            }
+            (Some(_), Some(l)) => {
+                return Err(Error::new(l.span(), "there can be at most one lifetime"))
+            }
+        }
+    };
+
+    let mut keys = vec![];
+    let mut finishes = vec![];
+    let mut columns = vec![];
+    for field in fields {
+        let attrs = FieldAttrs::parse(field)?;
+        if attrs.with.is_some() || attrs.flatten {
+            return Err(Error::new(
+                field.span(),
+                "with and flatten are not supported by PrestoBorrow",
+            ));
+        }
+        let key = field.ident.as_ref().unwrap();
+        let var = format_ident!("__{}", key);
+        let finish = if attrs.skip {
+            quote!(::std::default::Default::default())
+        } else if attrs.default {
+            quote!(#var.unwrap_or_default())
+        } else {
+            quote!(#var.expect("invalid context"))
+        };
+        keys.push(key);
+        finishes.push(finish);
+        if !attrs.skip {
+            columns.push((
+                Column {
+                    key,
+                    ty: &field.ty,
+                    attrs,
+                },
+                var,
+            ));
+        }
+    }
+    if columns.is_empty() {
+        return Err(Error::new(
+            data.ident.span(),
+            "there must be at least one field which is not skipped",
+        ));
+    }
+
+    let names = columns.iter().map(|(c, _)| c.name());
+    let presto_types = columns.iter().map(|(c, _)| {
+        let ty = c.ty;
+        let ret = quote!(<#ty as ::prusto::types::PrestoBorrow<#de>>::ty());
+        if c.attrs.default {
+            quote!(::prusto::types::PrestoTy::Defaulted(::std::boxed::Box::new(#ret)))
+        } else {
+            ret
+        }
+    });
+    let types: Vec<_> = columns.iter().map(|(c, _)| c.ty).collect();
+    let vars: Vec<_> = columns.iter().map(|(_, v)| v).collect();
+    let indices = 0..columns.len();
+
+    let (impl_generics, _, where_clause) = generics.split_for_impl();
+    let (_, ty_generics, _) = data.generics.split_for_impl();
+
+    let mut seed_generics = generics.clone();
+    seed_generics.params.insert(0, parse_quote!('_a));
+    let (seed_impl_generics, seed_ty_generics, _) = seed_generics.split_for_impl();
+
+    let ret = quote! {
+        impl #impl_generics ::prusto::types::PrestoBorrow<#de> for #name #ty_generics #where_clause {
+            type Seed<'_a> = #seed_name #seed_ty_generics;
+
+            fn ty() -> ::prusto::types::PrestoTy {
+                let types = vec![ #((#names.into(), #presto_types)),* ];
+                ::prusto::types::PrestoTy::Row(types)
+            }
+
+            fn seed<'_a>(ctx: &'_a ::prusto::types::Context<'_a>) -> Self::Seed<'_a> {
+                if let ::prusto::types::PrestoTy::Row(types) = ctx.ty() {
+                    let row_map = ctx.row_map().expect("invalid context");
+                    if row_map.len() != types.len() {
+                        panic!("invalid context");
+                    }
+                    #seed_name {
+                        ctx,
+                        types,
+                        row_map,
+                        _marker: ::std::marker::PhantomData,
+                    }
+                } else {
+                    panic!("invalid context")
+                }
+            }
+        }
+
+        #vis struct #seed_name #seed_impl_generics #where_clause {
+            ctx: &'_a ::prusto::types::Context<'_a>,
+            types: &'_a [(::std::string::String, ::prusto::types::PrestoTy)],
+            row_map: &'_a [usize],
+            _marker: ::std::marker::PhantomData<(&#de (), #name #ty_generics)>,
+        }
+
+        impl #seed_impl_generics ::serde::de::DeserializeSeed<#de> for #seed_name #seed_ty_generics #where_clause {
+            type Value = #name #ty_generics;
+
+            fn deserialize<D>(self, deserializer: D) -> Result<Self::Value, D::Error>
+            where
+                D: ::serde::de::Deserializer<#de>,
+            {
+                deserializer.deserialize_seq(self)
+            }
+        }
+
+        impl #seed_impl_generics ::serde::de::Visitor<#de> for #seed_name #seed_ty_generics #where_clause {
+            type Value = #name #ty_generics;
+
+            fn expecting(&self, formatter: &mut ::std::fmt::Formatter) -> ::std::fmt::Result {
+                formatter.write_str("sequence of same or different presto type")
+            }
+
+            fn visit_seq<_A: ::serde::de::SeqAccess<#de>>(self, mut seq: _A) -> Result<Self::Value, _A::Error> {
+                #( let mut #vars: ::std::option::Option<#types> = None; )*
+
+                for (idx, ty) in self.row_map.iter().zip(self.types.iter().map(|r| &r.1)) {
+                    let ctx = self.ctx.with_ty(ty);
+                    match *idx {
+                        #(
+                            #indices => {
+                                let seed = <#types as ::prusto::types::PrestoBorrow<#de>>::seed(&ctx);
+                                match seq.next_element_seed(seed)? {
+                                    Some(v) => #vars = Some(v),
+                                    None => return Err(<_A::Error as ::serde::de::Error>::custom("access seq failed, no more data")),
+                                }
+                            }
+                        )*
+                        _ => unreachable!(),
+                    }
+                }
+
+                if let Ok(None) = seq.next_element::<::serde::de::IgnoredAny>() {
+                    Ok(#name {
+                        #( #keys: #finishes, )*
+                    })
+                } else {
+                    Err(<_A::Error as ::serde::de::Error>::custom("access seq failed, there are some extra data"))
+                }
+            }
+        }
+    };
+
+    Ok(ret)
+}
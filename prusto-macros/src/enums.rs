@@ -0,0 +1,343 @@
+use super::*;
+
+// `#[presto(rename_all = "...")]`, same rules as serde
+#[derive(Clone, Copy)]
+enum RenameRule {
+    Lower,
+    Upper,
+    Pascal,
+    Camel,
+    Snake,
+    ScreamingSnake,
+    Kebab,
+    ScreamingKebab,
+}
+
+impl RenameRule {
+    fn parse(lit: &LitStr) -> Result<Self> {
+        let ret = match lit.value().as_str() {
+            "lowercase" => RenameRule::Lower,
+            "UPPERCASE" => RenameRule::Upper,
+            "PascalCase" => RenameRule::Pascal,
+            "camelCase" => RenameRule::Camel,
+            "snake_case" => RenameRule::Snake,
+            "SCREAMING_SNAKE_CASE" => RenameRule::ScreamingSnake,
+            "kebab-case" => RenameRule::Kebab,
+            "SCREAMING-KEBAB-CASE" => RenameRule::ScreamingKebab,
+            _ => return Err(Error::new(lit.span(), "unknown rename rule")),
+        };
+        Ok(ret)
+    }
+
+    // variant names are PascalCase
+    fn apply(self, name: &str) -> String {
+        match self {
+            RenameRule::Lower => name.to_ascii_lowercase(),
+            RenameRule::Upper => name.to_ascii_uppercase(),
+            RenameRule::Pascal => name.to_string(),
+            RenameRule::Camel => {
+                let mut chars = name.chars();
+                match chars.next() {
+                    Some(c) => c.to_ascii_lowercase().to_string() + chars.as_str(),
+                    None => String::new(),
+                }
+            }
+            RenameRule::Snake => {
+                let mut ret = String::new();
+                for (i, c) in name.char_indices() {
+                    if c.is_uppercase() && i > 0 {
+                        ret.push('_');
+                    }
+                    ret.push(c.to_ascii_lowercase());
+                }
+                ret
+            }
+            RenameRule::ScreamingSnake => RenameRule::Snake.apply(name).to_ascii_uppercase(),
+            RenameRule::Kebab => RenameRule::Snake.apply(name).replace('_', "-"),
+            RenameRule::ScreamingKebab => RenameRule::ScreamingSnake.apply(name).replace('_', "-"),
+        }
+    }
+}
+
+const REPRS: &[&str] = &["i8", "i16", "i32", "i64"];
+
+struct EnumAttrs {
+    rename_all: Option<RenameRule>,
+    repr: Option<Ident>,
+}
+
+impl EnumAttrs {
+    fn parse(data: &DeriveInput) -> Result<Self> {
+        let mut ret = EnumAttrs {
+            rename_all: None,
+            repr: None,
+        };
+        for nested in presto_metas(&data.attrs)? {
+            match nested {
+                NestedMeta::Meta(Meta::NameValue(nv)) if nv.path.is_ident("rename_all") => {
+                    ret.rename_all = Some(RenameRule::parse(lit_str(&nv.lit)?)?);
+                }
+                NestedMeta::Meta(Meta::NameValue(nv)) if nv.path.is_ident("repr") => {
+                    let lit = lit_str(&nv.lit)?;
+                    if !REPRS.contains(&lit.value().as_str()) {
+                        return Err(Error::new(
+                            lit.span(),
+                            "repr must be one of i8, i16, i32, i64",
+                        ));
+                    }
+                    ret.repr = Some(lit.parse()?);
+                }
+                n => return Err(Error::new(n.span(), "unknown presto attribute")),
+            }
+        }
+        if ret.repr.is_some() && ret.rename_all.is_some() {
+            return Err(Error::new(
+                data.ident.span(),
+                "rename_all can not be used with repr",
+            ));
+        }
+        Ok(ret)
+    }
+}
+
+struct VariantAttrs {
+    rename: Option<String>,
+    other: bool,
+}
+
+impl VariantAttrs {
+    fn parse(variant: &Variant) -> Result<Self> {
+        let mut ret = VariantAttrs {
+            rename: None,
+            other: false,
+        };
+        for nested in presto_metas(&variant.attrs)? {
+            match nested {
+                NestedMeta::Meta(Meta::NameValue(nv)) if nv.path.is_ident("rename") => {
+                    ret.rename = Some(lit_str(&nv.lit)?.value());
+                }
+                NestedMeta::Meta(Meta::Path(p)) if p.is_ident("other") => ret.other = true,
+                n => return Err(Error::new(n.span(), "unknown presto attribute")),
+            }
+        }
+        Ok(ret)
+    }
+}
+
+// unit only enums, they are read from varchar columns by name or from integer columns by
+// discriminant if `repr` is given
+pub(crate) fn derive_enum(data: &DeriveInput, e: &DataEnum) -> Result<TokenStream> {
+    let name = &data.ident;
+    if !data.generics.params.is_empty() {
+        return Err(Error::new(
+            data.generics.span(),
+            "generic enum is not supported",
+        ));
+    }
+    if e.variants.is_empty() {
+        return Err(Error::new(
+            name.span(),
+            "enum must have at least one variant",
+        ));
+    }
+
+    let attrs = EnumAttrs::parse(data)?;
+    let mut idents = vec![];
+    let mut names = vec![];
+    let mut other = None;
+    for variant in &e.variants {
+        if !matches!(variant.fields, Fields::Unit) {
+            return Err(Error::new(variant.span(), "variant must be unit"));
+        }
+        let v_attrs = VariantAttrs::parse(variant)?;
+        if v_attrs.other {
+            if other.is_some() {
+                return Err(Error::new(
+                    variant.span(),
+                    "there can be only one other variant",
+                ));
+            }
+            other = Some(&variant.ident);
+        }
+        if attrs.repr.is_some() && v_attrs.rename.is_some() {
+            return Err(Error::new(
+                variant.span(),
+                "rename can not be used with repr",
+            ));
+        }
+        let s = match (v_attrs.rename, attrs.rename_all) {
+            (Some(s), _) => s,
+            (None, Some(rule)) => rule.apply(&variant.ident.to_string()),
+            (None, None) => variant.ident.to_string(),
+        };
+        names.push(LitStr::new(&s, variant.ident.span()));
+        idents.push(&variant.ident);
+    }
+
+    match &attrs.repr {
+        Some(repr) => derive_int_enum(data, repr, &idents, other),
+        None => derive_str_enum(data, &idents, &names, other),
+    }
+}
+
+fn derive_str_enum(
+    data: &DeriveInput,
+    idents: &[&Ident],
+    names: &[LitStr],
+    other: Option<&Ident>,
+) -> Result<TokenStream> {
+    let name = &data.ident;
+    let vis = &data.vis;
+    let seed_name = format_ident!("__{}Seed", name);
+    let first = idents[0];
+    let expecting = format!("a variant of {}", name);
+    let fallback = match other {
+        Some(other) => quote!(Ok(#name::#other)),
+        None => quote!(Err(::serde::de::Error::unknown_variant(v, &[#(#names),*]))),
+    };
+
+    let ret = quote! {
+        impl ::prusto::types::Presto for #name {
+            type ValueType<'_a> = &'static str;
+            type Seed<'_a, '_de> = #seed_name;
+
+            fn value(&self) -> Self::ValueType<'_> {
+                match self {
+                    #( #name::#idents => #names, )*
+                }
+            }
+
+            fn ty() -> ::prusto::types::PrestoTy {
+                ::prusto::types::PrestoTy::Varchar(None)
+            }
+
+            fn seed<'_a, '_de>(_ctx: &'_a ::prusto::types::Context<'_a>) -> Self::Seed<'_a, '_de> {
+                #seed_name
+            }
+
+            fn empty() -> Self {
+                #name::#first
+            }
+        }
+
+        impl ::prusto::types::PrestoMapKey for #name {}
+
+        #vis struct #seed_name;
+
+        impl<'_de> ::serde::de::DeserializeSeed<'_de> for #seed_name {
+            type Value = #name;
+
+            fn deserialize<D>(self, deserializer: D) -> Result<Self::Value, D::Error>
+            where
+                D: ::serde::de::Deserializer<'_de>,
+            {
+                deserializer.deserialize_str(self)
+            }
+        }
+
+        impl<'_de> ::serde::de::Visitor<'_de> for #seed_name {
+            type Value = #name;
+
+            fn expecting(&self, formatter: &mut ::std::fmt::Formatter) -> ::std::fmt::Result {
+                formatter.write_str(#expecting)
+            }
+
+            fn visit_str<E: ::serde::de::Error>(self, v: &str) -> Result<Self::Value, E> {
+                match v {
+                    #( #names => Ok(#name::#idents), )*
+                    _ => #fallback,
+                }
+            }
+        }
+    };
+    Ok(ret)
+}
+
+fn derive_int_enum(
+    data: &DeriveInput,
+    repr: &Ident,
+    idents: &[&Ident],
+    other: Option<&Ident>,
+) -> Result<TokenStream> {
+    let name = &data.ident;
+    let vis = &data.vis;
+    let seed_name = format_ident!("__{}Seed", name);
+    let first = idents[0];
+    let fallback = match other {
+        Some(other) => quote!(Ok(#name::#other)),
+        None => quote! {
+            Err(::serde::de::Error::custom(format!("unknown value {} of {}", v, stringify!(#name))))
+        },
+    };
+
+    // `repr` is not rust's `#[repr]`, so the compiler does not check the discriminants fit in
+    let overflows = idents.iter().map(|ident| {
+        LitStr::new(
+            &format!(
+                "discriminant of {}::{} does not fit in {}",
+                name, ident, repr
+            ),
+            Ident::span(ident),
+        )
+    });
+
+    let ret = quote! {
+        const _: () = {
+            #(
+                assert!(
+                    #name::#idents as i128 >= #repr::MIN as i128
+                        && #name::#idents as i128 <= #repr::MAX as i128,
+                    #overflows
+                );
+            )*
+        };
+
+        impl ::prusto::types::Presto for #name {
+            type ValueType<'_a> = #repr;
+            type Seed<'_a, '_de> = #seed_name<'_a>;
+
+            fn value(&self) -> Self::ValueType<'_> {
+                match self {
+                    #( #name::#idents => #name::#idents as #repr, )*
+                }
+            }
+
+            fn ty() -> ::prusto::types::PrestoTy {
+                <#repr as ::prusto::types::Presto>::ty()
+            }
+
+            fn seed<'_a, '_de>(ctx: &'_a ::prusto::types::Context<'_a>) -> Self::Seed<'_a, '_de> {
+                #seed_name { ctx }
+            }
+
+            fn empty() -> Self {
+                #name::#first
+            }
+        }
+
+        impl ::prusto::types::PrestoMapKey for #name {}
+
+        #vis struct #seed_name<'_a> {
+            ctx: &'_a ::prusto::types::Context<'_a>,
+        }
+
+        impl<'_a, '_de> ::serde::de::DeserializeSeed<'_de> for #seed_name<'_a> {
+            type Value = #name;
+
+            fn deserialize<D>(self, deserializer: D) -> Result<Self::Value, D::Error>
+            where
+                D: ::serde::de::Deserializer<'_de>,
+            {
+                let seed = <#repr as ::prusto::types::Presto>::seed(self.ctx);
+                let v = ::serde::de::DeserializeSeed::deserialize(seed, deserializer)?;
+                #(
+                    if v == #name::#idents as #repr {
+                        return Ok(#name::#idents);
+                    }
+                )*
+                #fallback
+            }
+        }
+    };
+    Ok(ret)
+}
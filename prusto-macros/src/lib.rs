@@ -3,38 +3,327 @@ use quote::*;
 use syn::spanned::Spanned;
 use syn::*;
 
-#[proc_macro_derive(Presto)]
+mod borrow;
+mod enums;
+mod tuple_struct;
+
+use borrow::derive_borrow_impl;
+use enums::derive_enum;
+use tuple_struct::{derive_transparent, derive_tuple};
+
+#[proc_macro_derive(Presto, attributes(presto))]
 pub fn derive(input: proc_macro::TokenStream) -> proc_macro::TokenStream {
-    let data = parse_macro_input!(input as ItemStruct);
+    let data = parse_macro_input!(input as DeriveInput);
+
+    let ret = match &data.data {
+        Data::Struct(s) => derive_impl(&data, &s.fields),
+        Data::Enum(e) => derive_enum(&data, e),
+        Data::Union(_) => Err(Error::new(data.span(), "union is not supported")),
+    };
+    match ret {
+        Ok(d) => d.into(),
+        Err(e) => e.to_compile_error().into(),
+    }
+}
+
+// structs which borrow from the decoded data, they are decode only
+#[proc_macro_derive(PrestoBorrow, attributes(presto))]
+pub fn derive_borrow(input: proc_macro::TokenStream) -> proc_macro::TokenStream {
+    let data = parse_macro_input!(input as DeriveInput);
 
-    match derive_impl(data) {
+    match derive_borrow_impl(&data) {
         Ok(d) => d.into(),
         Err(e) => e.to_compile_error().into(),
     }
 }
 
-fn derive_impl(data: ItemStruct) -> Result<TokenStream> {
+struct StructAttrs {
+    transparent: bool,
+}
+
+impl StructAttrs {
+    fn parse(data: &DeriveInput) -> Result<Self> {
+        let mut ret = StructAttrs { transparent: false };
+        for nested in presto_metas(&data.attrs)? {
+            match nested {
+                NestedMeta::Meta(Meta::Path(p)) if p.is_ident("transparent") => {
+                    ret.transparent = true
+                }
+                n => return Err(Error::new(n.span(), "unknown presto attribute")),
+            }
+        }
+        Ok(ret)
+    }
+}
+
+struct FieldAttrs {
+    rename: Option<String>,
+    skip: bool,
+    default: bool,
+    with: Option<Path>,
+    flatten: bool,
+}
+
+impl FieldAttrs {
+    fn parse(field: &Field) -> Result<Self> {
+        let mut ret = FieldAttrs {
+            rename: None,
+            skip: false,
+            default: false,
+            with: None,
+            flatten: false,
+        };
+        for nested in presto_metas(&field.attrs)? {
+            match nested {
+                NestedMeta::Meta(Meta::NameValue(nv)) if nv.path.is_ident("rename") => {
+                    ret.rename = Some(lit_str(&nv.lit)?.value());
+                }
+                NestedMeta::Meta(Meta::NameValue(nv)) if nv.path.is_ident("with") => {
+                    ret.with = Some(lit_str(&nv.lit)?.parse()?);
+                }
+                NestedMeta::Meta(Meta::Path(p)) if p.is_ident("skip") => ret.skip = true,
+                NestedMeta::Meta(Meta::Path(p)) if p.is_ident("default") => ret.default = true,
+                NestedMeta::Meta(Meta::Path(p)) if p.is_ident("flatten") => ret.flatten = true,
+                n => return Err(Error::new(n.span(), "unknown presto attribute")),
+            }
+        }
+        if ret.skip && (ret.rename.is_some() || ret.default || ret.with.is_some() || ret.flatten) {
+            return Err(Error::new(
+                field.span(),
+                "skip can not be used with other presto attributes",
+            ));
+        }
+        if ret.flatten && (ret.rename.is_some() || ret.default || ret.with.is_some()) {
+            return Err(Error::new(
+                field.span(),
+                "flatten can not be used with other presto attributes",
+            ));
+        }
+        Ok(ret)
+    }
+}
+
+// all items in `#[presto(...)]` attributes
+fn presto_metas(attrs: &[Attribute]) -> Result<Vec<NestedMeta>> {
+    let mut ret = vec![];
+    for attr in attrs.iter().filter(|a| a.path.is_ident("presto")) {
+        match attr.parse_meta()? {
+            Meta::List(list) => ret.extend(list.nested),
+            m => return Err(Error::new(m.span(), "expected #[presto(...)]")),
+        }
+    }
+    Ok(ret)
+}
+
+fn lit_str(lit: &Lit) -> Result<&LitStr> {
+    match lit {
+        Lit::Str(s) => Ok(s),
+        _ => Err(Error::new(lit.span(), "expected string literal")),
+    }
+}
+
+// a field which maps to a column
+struct Column<'a> {
+    key: &'a Ident,
+    ty: &'a Type,
+    attrs: FieldAttrs,
+}
+
+impl Column<'_> {
+    fn name(&self) -> LitStr {
+        match &self.attrs.rename {
+            Some(name) => LitStr::new(name, self.key.span()),
+            None => LitStr::new(&self.key.to_string(), self.key.span()),
+        }
+    }
+
+    // the type which is sent and received, it is the proxy type for `with`
+    fn presto_ty(&self) -> Type {
+        match &self.attrs.with {
+            Some(with) => parse_quote!(#with::Proxy),
+            None => self.ty.clone(),
+        }
+    }
+
+    fn value_ty(&self) -> TokenStream {
+        let ty = self.ty;
+        match &self.attrs.with {
+            Some(with) => quote!(::prusto::types::WithValue<#with::Proxy>),
+            None => quote!(<#ty as ::prusto::types::Presto>::ValueType<'_a>),
+        }
+    }
+
+    fn value(&self) -> TokenStream {
+        let key = self.key;
+        match &self.attrs.with {
+            Some(with) => quote!(::prusto::types::WithValue(#with::to_presto(&self.#key))),
+            None => quote!(self.#key.value()),
+        }
+    }
+
+    fn presto_ty_expr(&self) -> TokenStream {
+        let ty = self.presto_ty();
+        let ret = quote!(<#ty as ::prusto::types::Presto>::ty());
+        if self.attrs.default {
+            quote!(::prusto::types::PrestoTy::Defaulted(::std::boxed::Box::new(#ret)))
+        } else {
+            ret
+        }
+    }
+
+    fn ty_push(&self) -> TokenStream {
+        let ty = self.ty;
+        if self.attrs.flatten {
+            // an empty row is rejected by `Context`, only rows with fields can be flattened
+            let name = self.name();
+            quote! {
+                match <#ty as ::prusto::types::Presto>::ty() {
+                    ::prusto::types::PrestoTy::Row(r) => types.extend(r),
+                    _ => types.push((#name.into(), ::prusto::types::PrestoTy::Row(::std::vec::Vec::new()))),
+                }
+            }
+        } else {
+            let name = self.name();
+            let presto_ty = self.presto_ty_expr();
+            quote!(types.push((#name.into(), #presto_ty));)
+        }
+    }
+
+    // number of columns
+    fn count(&self) -> TokenStream {
+        let ty = self.ty;
+        if self.attrs.flatten {
+            quote!(<#ty as ::prusto::types::RowFields>::FIELDS)
+        } else {
+            quote!(1)
+        }
+    }
+
+    fn serialize_field(&self) -> TokenStream {
+        let key = self.key;
+        if self.attrs.flatten {
+            quote!(::prusto::types::RowFields::serialize_fields(&self.#key, s)?;)
+        } else {
+            let value = self.value();
+            quote!(s.serialize_element(&#value)?;)
+        }
+    }
+
+    // decode the column at `idx` if it belongs to this field, `offset` is the index of its
+    // first column
+    fn access_field(&self, offset: &TokenStream) -> TokenStream {
+        let key = self.key;
+        if self.attrs.flatten {
+            let count = self.count();
+            quote! {
+                let i = idx.wrapping_sub(#offset);
+                if i < #count {
+                    return ::prusto::types::RowFields::access_field(&mut self.#key, i, seq, ctx);
+                }
+            }
+        } else {
+            let seed = self.seed();
+            quote! {
+                if idx == #offset {
+                    let seed = #seed;
+                    return match seq.next_element_seed(seed)? {
+                        Some(data) => {
+                            self.#key = data;
+                            Ok(())
+                        }
+                        None => Err(<_A::Error as ::serde::de::Error>::custom("access seq failed, no more data")),
+                    };
+                }
+            }
+        }
+    }
+
+    fn seed(&self) -> TokenStream {
+        let ty = self.presto_ty();
+        let seed = quote!(<#ty as ::prusto::types::Presto>::seed(ctx));
+        match &self.attrs.with {
+            Some(with) => quote!(::prusto::types::WithSeed::new(#seed, #with::from_presto)),
+            None => seed,
+        }
+    }
+}
+
+// skipped, defaulted and `with` fields need `Default`
+fn empty_value(field: &Field, attrs: &FieldAttrs) -> TokenStream {
+    let ty = &field.ty;
+    if attrs.skip || attrs.default || attrs.with.is_some() {
+        quote!(::std::default::Default::default())
+    } else {
+        quote!(<#ty as ::prusto::types::Presto>::empty())
+    }
+}
+
+fn derive_impl(data: &DeriveInput, fields: &Fields) -> Result<TokenStream> {
+    if StructAttrs::parse(data)?.transparent {
+        return derive_transparent(data, fields);
+    }
+
     let name = &data.ident;
     let vis = &data.vis;
     let seed_name = format_ident!("__{}Seed", name);
-    let fields: Vec<Field> = match data.fields {
-        Fields::Named(d) => d.named.into_iter().collect(),
-        Fields::Unnamed(d) => return Err(Error::new(d.span(), "field must be named")),
+    let fields: Vec<&Field> = match fields {
+        Fields::Named(d) => d.named.iter().collect(),
+        Fields::Unnamed(d) => return derive_tuple(data, d),
         Fields::Unit => return Err(Error::new(data.span(), "field can not be unit")),
     };
 
-    let tuplety = Ident::new(&format!("Tuple{}", fields.len()), Span::call_site());
+    let mut columns = vec![];
+    let mut empties = vec![];
+    for field in fields.iter().copied() {
+        let attrs = FieldAttrs::parse(field)?;
+        empties.push(empty_value(field, &attrs));
+        if !attrs.skip {
+            columns.push(Column {
+                key: field.ident.as_ref().unwrap(),
+                ty: &field.ty,
+                attrs,
+            });
+        }
+    }
+    if columns.is_empty() {
+        return Err(Error::new(
+            data.ident.span(),
+            "there must be at least one field which is not skipped",
+        ));
+    }
+    // the names of flattened columns are only known at runtime, they are checked by `Context`
+    for (i, c) in columns.iter().enumerate().filter(|(_, c)| !c.attrs.flatten) {
+        let name = c.name().value();
+        if columns[..i]
+            .iter()
+            .any(|prev| !prev.attrs.flatten && prev.name().value() == name)
+        {
+            return Err(Error::new(
+                c.key.span(),
+                format!("duplicate column name `{}`", name),
+            ));
+        }
+    }
 
-    let keys = fields.iter().map(|f| f.ident.as_ref().unwrap());
-    let keys_lit = keys
-        .clone()
-        .map(|ident| LitStr::new(&format!("{}", ident), ident.span()));
-    let types = fields.iter().map(|f| &f.ty);
-    let types1 = types.clone();
+    let all_keys = fields.iter().map(|f| f.ident.as_ref().unwrap());
+    let types = columns.iter().map(|c| c.ty);
+    let ty_pushes = columns.iter().map(|c| c.ty_push());
 
-    let keys2 = keys.clone();
-    let types2 = types.clone();
-    let types3 = types.clone();
+    // the value of flattened fields are spliced into the parent's
+    let (value_type, value) = if columns.iter().any(|c| c.attrs.flatten) {
+        (
+            quote!(::prusto::types::FlatValue<'_a, Self>),
+            quote!(::prusto::types::FlatValue(self)),
+        )
+    } else {
+        let tuplety = Ident::new(&format!("Tuple{}", columns.len()), Span::call_site());
+        let value_types = columns.iter().map(|c| c.value_ty());
+        let values = columns.iter().map(|c| c.value());
+        (
+            quote!(::prusto::tuples::#tuplety< #(#value_types, )* >),
+            quote!(::prusto::tuples::#tuplety( #(#values, )* )),
+        )
+    };
 
     let (impl_generics, ty_generics, where_clause) = data.generics.split_for_impl();
 
@@ -49,15 +338,16 @@ fn derive_impl(data: ItemStruct) -> Result<TokenStream> {
     let impl_trait_block = quote! {
 
         impl #impl_generics ::prusto::types::Presto for #name #ty_generics #where_clause {
-            type ValueType<'_a> where #(#types: '_a ,)* = ::prusto::tuples::#tuplety< #(<#types1 as ::prusto::types::Presto>::ValueType<'_a>, )* >;
+            type ValueType<'_a> where #(#types: '_a ,)* = #value_type;
             type Seed<'_a, '_de> = #seed_name #seed_ty_generics;
 
             fn value(&self) -> Self::ValueType<'_>  {
-                ::prusto::tuples::#tuplety( #(self.#keys.value(), )* )
+                #value
             }
 
             fn ty() -> ::prusto::types::PrestoTy {
-                let types = vec![ #((#keys_lit.into(), <#types2 as ::prusto::types::Presto>::ty())),* ];
+                let mut types = ::std::vec::Vec::new();
+                #( #ty_pushes )*
                 ::prusto::types::PrestoTy::Row(types)
             }
 
@@ -80,7 +370,7 @@ fn derive_impl(data: ItemStruct) -> Result<TokenStream> {
 
             fn empty() -> Self {
                 Self {
-                    #( #keys2:  <#types3 as ::prusto::types::Presto>::empty(),)*
+                    #( #all_keys: #empties,)*
                 }
             }
         }
@@ -115,7 +405,7 @@ fn derive_impl(data: ItemStruct) -> Result<TokenStream> {
 
                 for (idx, ty) in self.row_map.iter().zip(self.types.iter().map(|r| &r.1)) {
                     let ctx = self.ctx.with_ty(ty);
-                    ret.__access_seq(*idx, &mut seq, &ctx)?;
+                    ::prusto::types::RowFields::access_field(&mut ret, *idx, &mut seq, &ctx)?;
                 }
 
                 if let Ok(None) = seq.next_element::<String>() {
@@ -127,7 +417,7 @@ fn derive_impl(data: ItemStruct) -> Result<TokenStream> {
         }
     };
 
-    let impl_block = access_seq(&fields, name, &data.generics)?;
+    let impl_block = row_fields(&columns, name, &data.generics)?;
 
     let ret = quote! {
         #impl_trait_block
@@ -137,34 +427,47 @@ fn derive_impl(data: ItemStruct) -> Result<TokenStream> {
     Ok(ret)
 }
 
-fn access_seq(fields: &[Field], name: &Ident, generics: &Generics) -> Result<TokenStream> {
-    let indices = (0..fields.len()).map(|i| LitInt::new(&format!("{}", i), fields[i].span()));
-    let keys = fields.iter().map(|f| f.ident.as_ref().unwrap());
-    let types = fields.iter().map(|f| &f.ty);
+fn row_fields(columns: &[Column], name: &Ident, generics: &Generics) -> Result<TokenStream> {
+    let counts: Vec<_> = columns.iter().map(|c| c.count()).collect();
+    let serialize_fields = columns.iter().map(|c| c.serialize_field());
+
+    let mut accesses = vec![];
+    let mut prev = 0usize;
+    let mut prev_flatten = vec![];
+    for (c, count) in columns.iter().zip(&counts) {
+        let offset = if prev_flatten.is_empty() {
+            quote!(#prev)
+        } else if prev == 0 {
+            quote!(#(#prev_flatten)+*)
+        } else {
+            quote!(#prev #(+ #prev_flatten)*)
+        };
+        accesses.push(c.access_field(&offset));
+        if c.attrs.flatten {
+            prev_flatten.push(count.clone());
+        } else {
+            prev += 1;
+        }
+    }
+
     let (impl_generics, ty_generics, where_clause) = generics.split_for_impl();
 
-    let access_seq = quote! {
-        impl #impl_generics #name #ty_generics #where_clause {
-            fn __access_seq<'_a, '_de, _A: ::serde::de::SeqAccess<'_de>>(&mut self, idx: usize, seq: &mut _A, ctx: &'_a ::prusto::types::Context<'_a>)
+    let ret = quote! {
+        impl #impl_generics ::prusto::types::RowFields for #name #ty_generics #where_clause {
+            const FIELDS: usize = #(#counts)+*;
+
+            fn serialize_fields<_S: ::serde::ser::SerializeTuple>(&self, s: &mut _S) -> ::std::result::Result<(), _S::Error> {
+                #(#serialize_fields)*
+                Ok(())
+            }
+
+            fn access_field<'_a, '_de, _A: ::serde::de::SeqAccess<'_de>>(&mut self, idx: usize, seq: &mut _A, ctx: &'_a ::prusto::types::Context<'_a>)
                 -> ::std::result::Result<(), _A::Error> {
-                match idx {
-                    #(
-                        #indices => {
-                            let seed = <#types as ::prusto::types::Presto>::seed(ctx);
-                            let data = seq.next_element_seed(seed)?;
-                            if let Some(data) = data {
-                                self.#keys = data;
-                                Ok(())
-                            } else {
-                                Err(<_A::Error as ::serde::de::Error>::custom("access seq failed, no more data"))
-                            }
-                        },
-                    )*
-                    _ => unreachable!(),
-                }
+                #(#accesses)*
+                Err(<_A::Error as ::serde::de::Error>::custom(format!("no column {} in {}", idx, stringify!(#name))))
             }
         }
     };
 
-    Ok(access_seq)
+    Ok(ret)
 }
@@ -0,0 +1,163 @@
+use super::*;
+
+// a struct with a single field, it has the same presto type and encoding as the field
+pub(crate) fn derive_transparent(data: &DeriveInput, fields: &Fields) -> Result<TokenStream> {
+    let name = &data.ident;
+    if fields.len() != 1 {
+        return Err(Error::new(
+            data.span(),
+            "transparent struct must have exactly one field",
+        ));
+    }
+    let field = fields.iter().next().unwrap();
+    no_field_attrs(field)?;
+    let ty = &field.ty;
+    let empty = quote!(<#ty as ::prusto::types::Presto>::empty());
+    let (member, construct, empty) = match &field.ident {
+        Some(ident) => (
+            quote!(#ident),
+            quote!(|v| Ok(#name { #ident: v })),
+            quote!(#name { #ident: #empty }),
+        ),
+        None => (quote!(0), quote!(|v| Ok(#name(v))), quote!(#name(#empty))),
+    };
+    let (impl_generics, ty_generics, where_clause) = data.generics.split_for_impl();
+
+    let ret = quote! {
+        impl #impl_generics ::prusto::types::Presto for #name #ty_generics #where_clause {
+            type ValueType<'_a> where #ty: '_a = <#ty as ::prusto::types::Presto>::ValueType<'_a>;
+            type Seed<'_a, '_de> = ::prusto::types::WithSeed<
+                <#ty as ::prusto::types::Presto>::Seed<'_a, '_de>,
+                #ty,
+                Self,
+                ::std::convert::Infallible,
+            >;
+
+            fn value(&self) -> Self::ValueType<'_> {
+                self.#member.value()
+            }
+
+            fn ty() -> ::prusto::types::PrestoTy {
+                <#ty as ::prusto::types::Presto>::ty()
+            }
+
+            fn seed<'_a, '_de>(ctx: &'_a ::prusto::types::Context<'_a>) -> Self::Seed<'_a, '_de> {
+                let seed = <#ty as ::prusto::types::Presto>::seed(ctx);
+                ::prusto::types::WithSeed::new(seed, #construct)
+            }
+
+            fn empty() -> Self {
+                #empty
+            }
+        }
+    };
+    Ok(ret)
+}
+
+// a tuple struct is an anonymous row, its fields are matched by position, also against
+// named rows
+pub(crate) fn derive_tuple(data: &DeriveInput, fields: &FieldsUnnamed) -> Result<TokenStream> {
+    let name = &data.ident;
+    let vis = &data.vis;
+    let seed_name = format_ident!("__{}Seed", name);
+    for field in &fields.unnamed {
+        no_field_attrs(field)?;
+    }
+
+    let tuplety = Ident::new(&format!("Tuple{}", fields.unnamed.len()), Span::call_site());
+    let types: Vec<_> = fields.unnamed.iter().map(|f| &f.ty).collect();
+    let members = (0..types.len()).map(Index::from);
+    let indices = 0..types.len();
+    let vars: Vec<_> = (0..types.len())
+        .map(|i| format_ident!("__f{}", i))
+        .collect();
+
+    let (impl_generics, ty_generics, where_clause) = data.generics.split_for_impl();
+
+    let mut seed_generics = data.generics.clone();
+    seed_generics.params.push(parse_quote!('_a));
+    let (seed_impl_generics, seed_ty_generics, _) = seed_generics.split_for_impl();
+
+    let mut seed_de_generics = seed_generics.clone();
+    seed_de_generics.params.push(parse_quote!('_de));
+    let (seed_de_impl_generics, _, _) = seed_de_generics.split_for_impl();
+
+    let ret = quote! {
+        impl #impl_generics ::prusto::types::Presto for #name #ty_generics #where_clause {
+            type ValueType<'_a> where #(#types: '_a ,)* = ::prusto::tuples::#tuplety< #(<#types as ::prusto::types::Presto>::ValueType<'_a>, )* >;
+            type Seed<'_a, '_de> = #seed_name #seed_ty_generics;
+
+            fn value(&self) -> Self::ValueType<'_> {
+                ::prusto::tuples::#tuplety( #(self.#members.value(), )* )
+            }
+
+            fn ty() -> ::prusto::types::PrestoTy {
+                ::prusto::types::PrestoTy::Tuple(vec![ #(<#types as ::prusto::types::Presto>::ty()),* ])
+            }
+
+            fn seed<'_a, '_de>(ctx: &'_a ::prusto::types::Context<'_a>) -> Self::Seed<'_a, '_de> {
+                #seed_name {
+                    ctx,
+                    _marker: ::std::marker::PhantomData,
+                }
+            }
+
+            fn empty() -> Self {
+                #name( #(<#types as ::prusto::types::Presto>::empty(), )* )
+            }
+        }
+
+        #vis struct #seed_name #seed_impl_generics #where_clause {
+            ctx: &'_a ::prusto::types::Context<'_a>,
+            _marker: ::std::marker::PhantomData<#name #ty_generics>,
+        }
+
+        impl #seed_de_impl_generics ::serde::de::DeserializeSeed<'_de> for #seed_name #seed_ty_generics #where_clause {
+            type Value = #name #ty_generics;
+
+            fn deserialize<D>(self, deserializer: D) -> Result<Self::Value, D::Error>
+            where
+                D: ::serde::de::Deserializer<'_de>,
+            {
+                deserializer.deserialize_seq(self)
+            }
+        }
+
+        impl #seed_de_impl_generics ::serde::de::Visitor<'_de> for #seed_name #seed_ty_generics #where_clause {
+            type Value = #name #ty_generics;
+
+            fn expecting(&self, formatter: &mut ::std::fmt::Formatter) -> ::std::fmt::Result {
+                formatter.write_str("sequence of same or different presto type")
+            }
+
+            fn visit_seq<_A: ::serde::de::SeqAccess<'_de>>(self, mut seq: _A) -> Result<Self::Value, _A::Error> {
+                #(
+                    let ty = self.ctx.ty().field_ty(#indices).expect("invalid context");
+                    let ctx = self.ctx.with_ty(ty);
+                    let seed = <#types as ::prusto::types::Presto>::seed(&ctx);
+                    let #vars = match seq.next_element_seed(seed)? {
+                        Some(v) => v,
+                        None => return Err(<_A::Error as ::serde::de::Error>::custom("access seq failed, no more data")),
+                    };
+                )*
+
+                if let Ok(None) = seq.next_element::<String>() {
+                    Ok(#name( #(#vars, )* ))
+                } else {
+                    Err(<_A::Error as ::serde::de::Error>::custom("access seq failed, there are some extra data"))
+                }
+            }
+        }
+    };
+    Ok(ret)
+}
+
+fn no_field_attrs(field: &Field) -> Result<()> {
+    match presto_metas(&field.attrs)?.first() {
+        Some(n) => Err(Error::new(
+            n.span(),
+            "presto attributes are not supported on this field",
+        )),
+        None => Ok(()),
+    }
+}
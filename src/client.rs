@@ -1,5 +1,15 @@
 use std::collections::{HashMap, HashSet};
-
+#[cfg(feature = "parquet")]
+use std::fs::File;
+#[cfg(feature = "parquet")]
+use std::path::Path;
+#[cfg(feature = "arrow")]
+use std::sync::Arc;
+
+#[cfg(feature = "arrow")]
+use arrow_array::RecordBatch;
+#[cfg(feature = "arrow")]
+use futures::stream::{self, Stream};
 use http::header::{ACCEPT_ENCODING, USER_AGENT};
 use http::StatusCode;
 use iterable::*;
@@ -11,6 +21,7 @@ use tokio::time::{sleep, Duration};
 
 use crate::auth::Auth;
 use crate::error::{Error, Result};
+use crate::format::RowWriter;
 #[cfg(not(feature = "presto"))]
 use crate::header::*;
 #[cfg(feature = "presto")]
@@ -20,7 +31,9 @@ use crate::selected_role::SelectedRole;
 use crate::session::{Session, SessionBuilder};
 use crate::ssl::Ssl;
 use crate::transaction::TransactionId;
-use crate::{DataSet, Presto, QueryResult, Row};
+#[cfg(feature = "parquet")]
+use crate::types::ParquetWriter;
+use crate::{DataSet, Page, Presto, QueryResult, Row};
 
 // TODO:
 // allow_redirects
@@ -436,6 +449,66 @@ impl Client {
         }
     }
 
+    /// yield a `RecordBatch` for every page with data, the schema is built from the columns of
+    /// the first page
+    #[cfg(feature = "arrow")]
+    pub fn get_record_batches(&self, sql: String) -> impl Stream<Item = Result<RecordBatch>> + '_ {
+        let state = (Some(sql), None::<String>, None);
+        stream::try_unfold(state, move |(mut sql, mut next, mut schema)| async move {
+            loop {
+                let res = if let Some(sql) = sql.take() {
+                    self.get_retry::<Row>(sql).await?
+                } else if let Some(url) = next.take() {
+                    self.get_next_retry::<Row>(&url).await?
+                } else {
+                    return Ok(None);
+                };
+                next = res.next_uri;
+
+                if let Some(d) = res.data_set {
+                    let s = schema.get_or_insert_with(|| Arc::new(d.arrow_schema()));
+                    if !d.is_empty() {
+                        let batch = d.record_batch(s.clone())?;
+                        return Ok(Some((batch, (sql, next, schema))));
+                    }
+                }
+            }
+        })
+    }
+
+    /// collect all pages into a `DataFrame`, the dtypes are built from the column types
+    #[cfg(feature = "polars")]
+    pub async fn get_dataframe(&self, sql: String) -> Result<polars::frame::DataFrame> {
+        let data = self.get_all::<Row>(sql).await?;
+        Ok(data.to_dataframe()?)
+    }
+
+    /// write every page with `writer` as soon as it arrives
+    pub async fn export(&self, sql: String, writer: &mut impl RowWriter) -> Result<()> {
+        let res = self.get_retry::<Row>(sql).await?;
+        if let Some(d) = &res.data_set {
+            writer.write(d)?;
+        }
+
+        let mut next = res.next_uri;
+        while let Some(url) = &next {
+            let res = self.get_next_retry::<Row>(url).await?;
+            next = res.next_uri;
+            if let Some(d) = &res.data_set {
+                writer.write(d)?;
+            }
+        }
+        writer.finish()?;
+        Ok(())
+    }
+
+    /// write the query into a parquet file, pages are written as they arrive
+    #[cfg(feature = "parquet")]
+    pub async fn export_parquet(&self, sql: String, path: impl AsRef<Path>) -> Result<()> {
+        let file = File::create(path)?;
+        self.export(sql, &mut ParquetWriter::new(file)).await
+    }
+
     pub async fn execute(&self, sql: String) -> Result<ExecuteResult> {
         let res = self.get_retry::<Row>(sql).await?;
 
@@ -456,25 +529,44 @@ impl Client {
     }
 
     pub async fn get<T: Presto + 'static>(&self, sql: String) -> Result<QueryResult<T>> {
+        let req = self.query_req(sql).await;
+        self.send(req).await
+    }
+
+    pub async fn get_next<T: Presto + 'static>(&self, url: &str) -> Result<QueryResult<T>> {
+        let req = self.next_req(url).await;
+        self.send(req).await
+    }
+
+    /// like `get` but keeps the response body, rows are decoded with `Page::rows`
+    pub async fn get_page(&self, sql: String) -> Result<Page> {
+        let req = self.query_req(sql).await;
+        self.send_page(req).await
+    }
+
+    pub async fn get_next_page(&self, url: &str) -> Result<Page> {
+        let req = self.next_req(url).await;
+        self.send_page(req).await
+    }
+
+    async fn query_req(&self, sql: String) -> RequestBuilder {
         let req = self.client.post(self.url.clone()).body(sql);
         let req = {
             let session = self.session.read().await;
             add_session_header(req, &session)
         };
 
-        let req = self.auth_req(req);
-        self.send(req).await
+        self.auth_req(req)
     }
 
-    pub async fn get_next<T: Presto + 'static>(&self, url: &str) -> Result<QueryResult<T>> {
+    async fn next_req(&self, url: &str) -> RequestBuilder {
         let req = self.client.get(url);
         let req = {
             let session = self.session.read().await;
             add_prepare_header(req, &session)
         };
 
-        let req = self.auth_req(req);
-        self.send(req).await
+        self.auth_req(req)
     }
 
     fn auth_req(&self, req: RequestBuilder) -> RequestBuilder {
@@ -488,6 +580,19 @@ impl Client {
     }
 
     async fn send<T: Presto + 'static>(&self, req: RequestBuilder) -> Result<QueryResult<T>> {
+        let resp = self.send_ok(req).await?;
+        let data = resp.json::<QueryResult<T>>().await?;
+        Ok(data)
+    }
+
+    async fn send_page(&self, req: RequestBuilder) -> Result<Page> {
+        let resp = self.send_ok(req).await?;
+        let body = resp.bytes().await?;
+        let page = Page::from_bytes(body)?;
+        Ok(page)
+    }
+
+    async fn send_ok(&self, req: RequestBuilder) -> Result<Response> {
         let resp = req.send().await?;
         let status = resp.status();
         if status != StatusCode::OK {
@@ -495,8 +600,7 @@ impl Client {
             Err(Error::HttpNotOk(status, data))
         } else {
             self.update_session(&resp).await;
-            let data = resp.json::<QueryResult<T>>().await?;
-            Ok(data)
+            Ok(resp)
         }
     }
 
@@ -26,6 +26,12 @@ pub enum Error {
     HttpError(#[from] reqwest::Error),
     #[error("http not ok, code: {0}, reason: {1}")]
     HttpNotOk(StatusCode, String),
+    #[error("decode json failed, reason: {0}")]
+    JsonError(#[from] serde_json::Error),
+    #[error("io error, reason: {0}")]
+    IoError(#[from] std::io::Error),
+    #[error("invalid data, reason: {0}")]
+    TypeError(#[from] crate::types::Error),
     #[error("query error, reason: {0}")]
     QueryError(#[from] QueryError),
     #[error("inconsistent data")]
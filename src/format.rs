@@ -0,0 +1,498 @@
+use std::io::{self, Write};
+
+use crate::{DataSet, PrestoTy, PrestoValue, Row};
+
+/// writes `DataSet<Row>`s in one of the output formats of the presto cli, a query can be
+/// written page by page and `finish` is called after the last one
+pub trait RowWriter {
+    /// write a page of rows, all pages of a query have the same columns
+    fn write(&mut self, data: &DataSet<Row>) -> io::Result<()>;
+
+    /// write what is left after the last page, e.g. the row count of a table
+    fn finish(&mut self) -> io::Result<()> {
+        Ok(())
+    }
+}
+
+impl<R: RowWriter + ?Sized> RowWriter for Box<R> {
+    fn write(&mut self, data: &DataSet<Row>) -> io::Result<()> {
+        (**self).write(data)
+    }
+
+    fn finish(&mut self) -> io::Result<()> {
+        (**self).finish()
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Format {
+    Csv,
+    CsvHeader,
+    Tsv,
+    TsvHeader,
+    JsonLines,
+    Aligned,
+    Vertical,
+    Markdown,
+}
+
+impl Format {
+    pub fn writer<'a, W: Write + 'a>(self, inner: W) -> Box<dyn RowWriter + 'a> {
+        match self {
+            Format::Csv => Box::new(CsvWriter::new(inner)),
+            Format::CsvHeader => Box::new(CsvWriter::new(inner).header(true)),
+            Format::Tsv => Box::new(TsvWriter::new(inner)),
+            Format::TsvHeader => Box::new(TsvWriter::new(inner).header(true)),
+            Format::JsonLines => Box::new(JsonLinesWriter::new(inner)),
+            Format::Aligned => Box::new(AlignedWriter::new(inner)),
+            Format::Vertical => Box::new(VerticalWriter::new(inner)),
+            Format::Markdown => Box::new(MarkdownWriter::new(inner)),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Quote {
+    /// quote every value, nulls are left empty
+    Always,
+    /// quote values with the delimiter, quotes or line breaks
+    Necessary,
+    Never,
+}
+
+pub struct CsvWriter<W> {
+    inner: W,
+    delimiter: char,
+    quote: Quote,
+    header: bool,
+    started: bool,
+}
+
+impl<W: Write> CsvWriter<W> {
+    pub fn new(inner: W) -> Self {
+        CsvWriter {
+            inner,
+            delimiter: ',',
+            quote: Quote::Necessary,
+            header: false,
+            started: false,
+        }
+    }
+
+    pub fn delimiter(mut self, d: char) -> Self {
+        self.delimiter = d;
+        self
+    }
+
+    pub fn quote(mut self, q: Quote) -> Self {
+        self.quote = q;
+        self
+    }
+
+    pub fn header(mut self, h: bool) -> Self {
+        self.header = h;
+        self
+    }
+
+    pub fn into_inner(self) -> W {
+        self.inner
+    }
+
+    fn write_record<'a>(
+        &mut self,
+        fields: impl Iterator<Item = Option<&'a str>>,
+    ) -> io::Result<()> {
+        for (i, field) in fields.enumerate() {
+            if i > 0 {
+                write!(self.inner, "{}", self.delimiter)?;
+            }
+            let field = match field {
+                Some(f) => f,
+                None => continue,
+            };
+            let quoted = match self.quote {
+                Quote::Always => true,
+                Quote::Necessary => field.contains([self.delimiter, '"', '\n', '\r']),
+                Quote::Never => false,
+            };
+            if quoted {
+                write!(self.inner, "\"{}\"", field.replace('"', "\"\""))?;
+            } else {
+                self.inner.write_all(field.as_bytes())?;
+            }
+        }
+        self.inner.write_all(b"\n")
+    }
+}
+
+impl<W: Write> RowWriter for CsvWriter<W> {
+    fn write(&mut self, data: &DataSet<Row>) -> io::Result<()> {
+        if !self.started {
+            self.started = true;
+            if self.header {
+                self.write_record(data.columns().iter().map(|(name, _)| Some(name.as_str())))?;
+            }
+        }
+        for row in data.as_slice() {
+            let fields = texts(data, row);
+            self.write_record(fields.iter().map(|f| f.as_deref()))?;
+        }
+        Ok(())
+    }
+
+    fn finish(&mut self) -> io::Result<()> {
+        self.inner.flush()
+    }
+}
+
+/// tabs, line breaks and backslashes in values are escaped with a backslash, nulls are empty
+pub struct TsvWriter<W> {
+    inner: W,
+    header: bool,
+    started: bool,
+}
+
+impl<W: Write> TsvWriter<W> {
+    pub fn new(inner: W) -> Self {
+        TsvWriter {
+            inner,
+            header: false,
+            started: false,
+        }
+    }
+
+    pub fn header(mut self, h: bool) -> Self {
+        self.header = h;
+        self
+    }
+
+    pub fn into_inner(self) -> W {
+        self.inner
+    }
+
+    fn write_record<'a>(
+        &mut self,
+        fields: impl Iterator<Item = Option<&'a str>>,
+    ) -> io::Result<()> {
+        for (i, field) in fields.enumerate() {
+            if i > 0 {
+                self.inner.write_all(b"\t")?;
+            }
+            if let Some(field) = field {
+                let field = field
+                    .replace('\\', "\\\\")
+                    .replace('\t', "\\t")
+                    .replace('\n', "\\n")
+                    .replace('\r', "\\r");
+                self.inner.write_all(field.as_bytes())?;
+            }
+        }
+        self.inner.write_all(b"\n")
+    }
+}
+
+impl<W: Write> RowWriter for TsvWriter<W> {
+    fn write(&mut self, data: &DataSet<Row>) -> io::Result<()> {
+        if !self.started {
+            self.started = true;
+            if self.header {
+                self.write_record(data.columns().iter().map(|(name, _)| Some(name.as_str())))?;
+            }
+        }
+        for row in data.as_slice() {
+            let fields = texts(data, row);
+            self.write_record(fields.iter().map(|f| f.as_deref()))?;
+        }
+        Ok(())
+    }
+
+    fn finish(&mut self) -> io::Result<()> {
+        self.inner.flush()
+    }
+}
+
+/// a json object per row keyed by the column names, values keep the form presto sends
+pub struct JsonLinesWriter<W> {
+    inner: W,
+}
+
+impl<W: Write> JsonLinesWriter<W> {
+    pub fn new(inner: W) -> Self {
+        JsonLinesWriter { inner }
+    }
+
+    pub fn into_inner(self) -> W {
+        self.inner
+    }
+}
+
+impl<W: Write> RowWriter for JsonLinesWriter<W> {
+    fn write(&mut self, data: &DataSet<Row>) -> io::Result<()> {
+        for row in data.as_slice() {
+            self.inner.write_all(b"{")?;
+            for (i, (name, _)) in data.columns().iter().enumerate() {
+                if i > 0 {
+                    self.inner.write_all(b",")?;
+                }
+                serde_json::to_writer(&mut self.inner, name)?;
+                self.inner.write_all(b":")?;
+                let value = value(row, i);
+                serde_json::to_writer(&mut self.inner, value)?;
+            }
+            self.inner.write_all(b"}\n")?;
+        }
+        Ok(())
+    }
+
+    fn finish(&mut self) -> io::Result<()> {
+        self.inner.flush()
+    }
+}
+
+/// the rows of all pages are kept until `finish`, so the widths of the columns fit every page,
+/// then the header, the rows and the row count are written
+pub struct AlignedWriter<W> {
+    inner: W,
+    columns: Vec<(String, PrestoTy)>,
+    rows: Vec<Vec<String>>,
+}
+
+impl<W: Write> AlignedWriter<W> {
+    pub fn new(inner: W) -> Self {
+        AlignedWriter {
+            inner,
+            columns: vec![],
+            rows: vec![],
+        }
+    }
+
+    pub fn into_inner(self) -> W {
+        self.inner
+    }
+
+    fn write_line(&mut self, cells: &[String]) -> io::Result<()> {
+        let line = format!(" {} ", cells.join(" | "));
+        writeln!(self.inner, "{}", line.trim_end())
+    }
+}
+
+impl<W: Write> RowWriter for AlignedWriter<W> {
+    fn write(&mut self, data: &DataSet<Row>) -> io::Result<()> {
+        if self.columns.is_empty() {
+            self.columns = data.columns().to_vec();
+        }
+        for row in data.as_slice() {
+            self.rows.push(values(data, row));
+        }
+        Ok(())
+    }
+
+    fn finish(&mut self) -> io::Result<()> {
+        let columns = std::mem::take(&mut self.columns);
+        let rows = std::mem::take(&mut self.rows);
+        let mut widths = columns
+            .iter()
+            .map(|(name, _)| width(name))
+            .collect::<Vec<_>>();
+        for row in &rows {
+            for (w, v) in widths.iter_mut().zip(row) {
+                *w = (*w).max(width(v));
+            }
+        }
+
+        if !columns.is_empty() {
+            let header = columns
+                .iter()
+                .zip(&widths)
+                .map(|((name, _), w)| center(name, *w))
+                .collect::<Vec<_>>();
+            self.write_line(&header)?;
+            let separator = widths.iter().map(|w| "-".repeat(w + 2)).collect::<Vec<_>>();
+            writeln!(self.inner, "{}", separator.join("+"))?;
+        }
+
+        for row in &rows {
+            let cells = row
+                .iter()
+                .zip(columns.iter().zip(&widths))
+                .map(|(v, ((_, ty), w))| pad(v, *w, is_numeric(ty)))
+                .collect::<Vec<_>>();
+            self.write_line(&cells)?;
+        }
+        match rows.len() {
+            1 => writeln!(self.inner, "(1 row)")?,
+            n => writeln!(self.inner, "({} rows)", n)?,
+        }
+        self.inner.flush()
+    }
+}
+
+/// every row is written as a record of `name | value` lines
+pub struct VerticalWriter<W> {
+    inner: W,
+    rows: usize,
+}
+
+impl<W: Write> VerticalWriter<W> {
+    pub fn new(inner: W) -> Self {
+        VerticalWriter { inner, rows: 0 }
+    }
+
+    pub fn into_inner(self) -> W {
+        self.inner
+    }
+}
+
+impl<W: Write> RowWriter for VerticalWriter<W> {
+    fn write(&mut self, data: &DataSet<Row>) -> io::Result<()> {
+        let columns = data.columns();
+        let name_width = columns.iter().map(|(name, _)| width(name)).max();
+        let name_width = name_width.unwrap_or(0);
+        for row in data.as_slice() {
+            self.rows += 1;
+            let values = values(data, row);
+            let value_width = values.iter().map(|v| width(v)).max().unwrap_or(0);
+            let header = format!("-[ RECORD {} ]", self.rows);
+            writeln!(
+                self.inner,
+                "{}+{}",
+                pad_with(&header, name_width + 1, '-'),
+                "-".repeat(value_width + 1)
+            )?;
+            for ((name, _), v) in columns.iter().zip(&values) {
+                let line = format!("{} | {}", pad(name, name_width, false), v);
+                writeln!(self.inner, "{}", line.trim_end())?;
+            }
+        }
+        Ok(())
+    }
+
+    fn finish(&mut self) -> io::Result<()> {
+        if self.rows == 0 {
+            writeln!(self.inner, "(no rows)")?;
+        }
+        self.inner.flush()
+    }
+}
+
+/// a github flavored markdown table, numbers are right aligned
+pub struct MarkdownWriter<W> {
+    inner: W,
+    started: bool,
+}
+
+impl<W: Write> MarkdownWriter<W> {
+    pub fn new(inner: W) -> Self {
+        MarkdownWriter {
+            inner,
+            started: false,
+        }
+    }
+
+    pub fn into_inner(self) -> W {
+        self.inner
+    }
+
+    fn write_line<T: AsRef<str>>(&mut self, cells: impl Iterator<Item = T>) -> io::Result<()> {
+        self.inner.write_all(b"|")?;
+        for cell in cells {
+            write!(self.inner, " {} |", cell.as_ref())?;
+        }
+        self.inner.write_all(b"\n")
+    }
+}
+
+impl<W: Write> RowWriter for MarkdownWriter<W> {
+    fn write(&mut self, data: &DataSet<Row>) -> io::Result<()> {
+        let columns = data.columns();
+        if !self.started {
+            self.started = true;
+            self.write_line(columns.iter().map(|(name, _)| escape_markdown(name)))?;
+            self.write_line(columns.iter().map(
+                |(_, ty)| {
+                    if is_numeric(ty) {
+                        "---:"
+                    } else {
+                        "---"
+                    }
+                },
+            ))?;
+        }
+        for row in data.as_slice() {
+            let values = values(data, row);
+            self.write_line(values.iter().map(|v| escape_markdown(v)))?;
+        }
+        Ok(())
+    }
+
+    fn finish(&mut self) -> io::Result<()> {
+        self.inner.flush()
+    }
+}
+
+fn value(row: &Row, idx: usize) -> &PrestoValue {
+    row.values().get(idx).unwrap_or(&PrestoValue::Null)
+}
+
+// nulls are rendered as `NULL`
+fn values(data: &DataSet<Row>, row: &Row) -> Vec<String> {
+    (0..data.columns().len())
+        .map(|idx| value(row, idx).to_string())
+        .collect()
+}
+
+// nulls are `None`, so delimited formats can leave them empty
+fn texts(data: &DataSet<Row>, row: &Row) -> Vec<Option<String>> {
+    (0..data.columns().len())
+        .map(|idx| match value(row, idx) {
+            PrestoValue::Null => None,
+            v => Some(v.to_string()),
+        })
+        .collect()
+}
+
+fn is_numeric(ty: &PrestoTy) -> bool {
+    match ty {
+        PrestoTy::PrestoInt(_)
+        | PrestoTy::PrestoFloat(_)
+        | PrestoTy::Decimal(_, _)
+        | PrestoTy::DynDecimal => true,
+        PrestoTy::Option(ty) | PrestoTy::Lenient(ty) | PrestoTy::Defaulted(ty) => is_numeric(ty),
+        _ => false,
+    }
+}
+
+fn width(s: &str) -> usize {
+    s.chars().count()
+}
+
+fn pad(s: &str, width: usize, right: bool) -> String {
+    let fill = " ".repeat(width.saturating_sub(self::width(s)));
+    if right {
+        format!("{}{}", fill, s)
+    } else {
+        format!("{}{}", s, fill)
+    }
+}
+
+fn pad_with(s: &str, width: usize, c: char) -> String {
+    let fill = c.to_string().repeat(width.saturating_sub(self::width(s)));
+    format!("{}{}", s, fill)
+}
+
+fn center(s: &str, width: usize) -> String {
+    let fill = width.saturating_sub(self::width(s));
+    format!(
+        "{}{}{}",
+        " ".repeat(fill / 2),
+        s,
+        " ".repeat(fill - fill / 2)
+    )
+}
+
+fn escape_markdown(s: &str) -> String {
+    s.replace('\\', "\\\\")
+        .replace('|', "\\|")
+        .replace("\r\n", "<br>")
+        .replace('\n', "<br>")
+}
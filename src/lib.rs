@@ -4,6 +4,7 @@
 pub mod auth;
 pub mod client;
 pub mod error;
+pub mod format;
 
 #[cfg(not(feature = "presto"))]
 mod header;
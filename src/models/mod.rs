@@ -1,5 +1,6 @@
 mod column;
 mod error;
+mod page;
 mod result;
 mod stat;
 mod ty;
@@ -7,6 +8,7 @@ mod warning;
 
 pub use column::*;
 pub use error::*;
+pub use page::*;
 pub use result::*;
 pub use stat::*;
 pub use ty::*;
@@ -0,0 +1,191 @@
+use std::fmt;
+use std::marker::PhantomData;
+
+use bytes::Bytes;
+use iterable::Iterable;
+use serde::de::{self, DeserializeSeed, Deserializer, IgnoredAny, MapAccess, SeqAccess, Visitor};
+use serde::Deserialize;
+
+use super::*;
+use crate::{Context, PrestoBorrow, PrestoTy};
+
+/// A page of query results which keeps the response body, rows are decoded from it on demand
+/// and may borrow from it, see `PrestoBorrow`.
+#[derive(Debug)]
+pub struct Page {
+    pub id: String,
+    pub info_uri: String,
+    pub partial_cancel_uri: Option<String>,
+    pub next_uri: Option<String>,
+
+    pub columns: Option<Vec<Column>>,
+    pub error: Option<QueryError>,
+
+    pub stats: Stat,
+    pub warnings: Vec<Warning>,
+
+    pub update_type: Option<String>,
+    pub update_count: Option<u64>,
+
+    body: Bytes,
+}
+
+// everything but the data
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct PageMeta {
+    id: String,
+    info_uri: String,
+    partial_cancel_uri: Option<String>,
+    next_uri: Option<String>,
+    columns: Option<Vec<Column>>,
+    error: Option<QueryError>,
+    stats: Stat,
+    warnings: Vec<Warning>,
+    update_type: Option<String>,
+    update_count: Option<u64>,
+}
+
+impl Page {
+    pub fn from_bytes(body: Bytes) -> serde_json::Result<Self> {
+        let meta: PageMeta = serde_json::from_slice(&body)?;
+        Ok(Page {
+            id: meta.id,
+            info_uri: meta.info_uri,
+            partial_cancel_uri: meta.partial_cancel_uri,
+            next_uri: meta.next_uri,
+            columns: meta.columns,
+            error: meta.error,
+            stats: meta.stats,
+            warnings: meta.warnings,
+            update_type: meta.update_type,
+            update_count: meta.update_count,
+            body,
+        })
+    }
+
+    pub fn body(&self) -> &[u8] {
+        &self.body
+    }
+
+    /// decode the rows of this page, they may borrow from it
+    pub fn rows<'de, T: PrestoBorrow<'de>>(&'de self) -> serde_json::Result<Vec<T>> {
+        let types = match &self.columns {
+            Some(columns) => columns
+                .clone()
+                .try_map(PrestoTy::from_column)
+                .map_err(|e| {
+                    de::Error::custom(format!("deserialize presto type failed, reason: {}", e))
+                })?,
+            None => vec![],
+        };
+        let provided = PrestoTy::Array(Box::new(PrestoTy::Row(types)));
+        let target = PrestoTy::Array(Box::new(T::ty()));
+
+        let mut deserializer = serde_json::Deserializer::from_slice(&self.body);
+        let seed = PageSeed {
+            target: &target,
+            provided: &provided,
+            _marker: PhantomData,
+        };
+        let ret = seed.deserialize(&mut deserializer)?;
+        deserializer.end()?;
+        Ok(ret)
+    }
+}
+
+#[derive(Deserialize)]
+#[serde(field_identifier, rename_all = "lowercase")]
+enum Field {
+    Data,
+    #[serde(other)]
+    Other,
+}
+
+// find `data` in the body and decode it
+struct PageSeed<'a, T> {
+    target: &'a PrestoTy,
+    provided: &'a PrestoTy,
+    _marker: PhantomData<T>,
+}
+
+impl<'a, 'de, T: PrestoBorrow<'de>> Visitor<'de> for PageSeed<'a, T> {
+    type Value = Vec<T>;
+    fn expecting(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+        formatter.write_str("struct QueryResults")
+    }
+
+    fn visit_map<V>(self, mut map: V) -> Result<Self::Value, V::Error>
+    where
+        V: MapAccess<'de>,
+    {
+        let mut ret = None;
+        while let Some(key) = map.next_key()? {
+            match key {
+                Field::Data if ret.is_some() => return Err(de::Error::duplicate_field("data")),
+                Field::Data => {
+                    let ctx = Context::from_target(self.target, self.provided).map_err(|e| {
+                        de::Error::custom(format!("invalid presto type, reason: {}", e))
+                    })?;
+                    ret = Some(map.next_value_seed(RowsSeed {
+                        ctx: &ctx,
+                        _marker: PhantomData,
+                    })?);
+                }
+                Field::Other => {
+                    map.next_value::<IgnoredAny>()?;
+                }
+            }
+        }
+        // it is empty when there is no data
+        Ok(ret.unwrap_or_default())
+    }
+}
+
+impl<'a, 'de, T: PrestoBorrow<'de>> DeserializeSeed<'de> for PageSeed<'a, T> {
+    type Value = Vec<T>;
+    fn deserialize<D>(self, deserializer: D) -> Result<Self::Value, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        deserializer.deserialize_map(self)
+    }
+}
+
+struct RowsSeed<'a, T> {
+    ctx: &'a Context<'a>,
+    _marker: PhantomData<T>,
+}
+
+impl<'a, 'de, T: PrestoBorrow<'de>> Visitor<'de> for RowsSeed<'a, T> {
+    type Value = Vec<T>;
+    fn expecting(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+        formatter.write_str("sequence of rows")
+    }
+
+    fn visit_seq<A>(self, mut seq: A) -> Result<Self::Value, A::Error>
+    where
+        A: SeqAccess<'de>,
+    {
+        let ty = match self.ctx.ty() {
+            PrestoTy::Array(ty) => ty,
+            _ => unreachable!(),
+        };
+        let ctx = self.ctx.with_ty(ty);
+        let mut ret = vec![];
+        while let Some(row) = seq.next_element_seed(T::seed(&ctx))? {
+            ret.push(row);
+        }
+        Ok(ret)
+    }
+}
+
+impl<'a, 'de, T: PrestoBorrow<'de>> DeserializeSeed<'de> for RowsSeed<'a, T> {
+    type Value = Vec<T>;
+    fn deserialize<D>(self, deserializer: D) -> Result<Self::Value, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        deserializer.deserialize_seq(self)
+    }
+}
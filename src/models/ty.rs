@@ -1,9 +1,10 @@
+use std::borrow::Cow;
 use std::fmt;
 
 use serde::de::{self, Visitor};
 use serde::{Deserialize, Deserializer, Serialize, Serializer};
 
-#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+#[derive(Clone, Debug, Eq, PartialEq)]
 pub enum RawPrestoTy {
     BigInt,
     Integer,
@@ -17,6 +18,8 @@ pub enum RawPrestoTy {
     HyperLogLog,
     QDigest,
     P4HyperLogLog,
+    TDigest,
+    SetDigest,
     IntervalDayToSecond,
     IntervalYearToMonth,
     Timestamp,
@@ -31,14 +34,20 @@ pub enum RawPrestoTy {
     Map,
     Json,
     IpAddress,
+    IpPrefix,
     Uuid,
+    Geometry,
+    SphericalGeography,
+    BingTile,
     Unknown,
+    // types this crate does not know, e.g. connector specific ones
+    Other(String),
 }
 
 impl RawPrestoTy {
-    pub fn to_str(&self) -> &'static str {
+    pub fn to_str(&self) -> Cow<'static, str> {
         use RawPrestoTy::*;
-        match *self {
+        let s = match self {
             BigInt => "bigint",
             Integer => "integer",
             SmallInt => "smallint",
@@ -51,6 +60,8 @@ impl RawPrestoTy {
             HyperLogLog => "HyperLogLog",
             QDigest => "qdigest",
             P4HyperLogLog => "P4HyperLogLog",
+            TDigest => "tdigest",
+            SetDigest => "SetDigest",
             IntervalDayToSecond => "interval day to second",
             IntervalYearToMonth => "interval year to month",
             Timestamp => "timestamp",
@@ -65,9 +76,15 @@ impl RawPrestoTy {
             Map => "map",
             Json => "json",
             IpAddress => "ipaddress",
+            IpPrefix => "ipprefix",
             Uuid => "uuid",
+            Geometry => "Geometry",
+            SphericalGeography => "SphericalGeography",
+            BingTile => "BingTile",
             Unknown => "unknown",
-        }
+            Other(s) => return s.clone().into(),
+        };
+        s.into()
     }
 
     pub fn parse(s: &str) -> Option<Self> {
@@ -85,6 +102,8 @@ impl RawPrestoTy {
             "HyperLogLog" => HyperLogLog,
             "qdigest" => QDigest,
             "P4HyperLogLog" => P4HyperLogLog,
+            "tdigest" => TDigest,
+            "SetDigest" => SetDigest,
             "interval day to second" => IntervalDayToSecond,
             "interval year to month" => IntervalYearToMonth,
             "timestamp" => Timestamp,
@@ -99,7 +118,11 @@ impl RawPrestoTy {
             "map" => Map,
             "json" => Json,
             "ipaddress" => IpAddress,
+            "ipprefix" => IpPrefix,
             "uuid" => Uuid,
+            "Geometry" => Geometry,
+            "SphericalGeography" => SphericalGeography,
+            "BingTile" => BingTile,
             "unknown" => Unknown,
             _ => return None,
         };
@@ -112,7 +135,7 @@ impl Serialize for RawPrestoTy {
     where
         S: Serializer,
     {
-        serializer.serialize_str(self.to_str())
+        serializer.serialize_str(&self.to_str())
     }
 }
 
@@ -134,10 +157,7 @@ impl<'de> Deserialize<'de> for RawPrestoTy {
             where
                 E: de::Error,
             {
-                match RawPrestoTy::parse(v) {
-                    Some(d) => Ok(d),
-                    None => Err(E::custom(format!("invalid presto type: {}", v))),
-                }
+                Ok(RawPrestoTy::parse(v).unwrap_or_else(|| RawPrestoTy::Other(v.to_string())))
             }
         }
 
@@ -170,7 +190,12 @@ mod tests {
         let ty = serde_json::from_str::<RawPrestoTy>(data).unwrap();
         assert_eq!(ty, RawPrestoTy::Json);
 
-        let invalid = "\"xxx\"";
+        let data = "\"color\"";
+        let ty = serde_json::from_str::<RawPrestoTy>(data).unwrap();
+        assert_eq!(ty, RawPrestoTy::Other("color".into()));
+        assert_eq!(serde_json::to_string(&ty).unwrap(), data);
+
+        let invalid = "1";
         let res = serde_json::from_str::<RawPrestoTy>(invalid);
         assert!(res.is_err());
     }
@@ -0,0 +1,477 @@
+use std::sync::Arc;
+
+use arrow_array::types::{
+    ArrowTimestampType, DurationMillisecondType, Float32Type, Float64Type, Int16Type, Int32Type,
+    Int64Type, Int8Type, IntervalYearMonthType, Time32MillisecondType, Time32SecondType,
+    Time64MicrosecondType, Time64NanosecondType, TimestampMicrosecondType,
+    TimestampMillisecondType, TimestampNanosecondType, TimestampSecondType, UInt32Type, UInt8Type,
+};
+use arrow_array::{
+    ArrayRef, ArrowPrimitiveType, BinaryArray, BooleanArray, Date32Array, Decimal128Array,
+    FixedSizeBinaryArray, ListArray, MapArray, NullArray, PrimitiveArray, RecordBatch, StringArray,
+    StructArray,
+};
+use arrow_buffer::{NullBuffer, OffsetBuffer};
+use arrow_schema::{DataType, Field, FieldRef, Fields, IntervalUnit, Schema, SchemaRef, TimeUnit};
+use bigdecimal::ToPrimitive;
+use chrono::{NaiveDate, NaiveDateTime, NaiveTime, Timelike};
+
+use super::{DataSet, Error, PrestoFloat, PrestoInt, PrestoTy, PrestoValue, Row};
+
+// arrow keeps the instant of zoned timestamps, the zone is the same for the whole column
+const TIME_ZONE: &str = "UTC";
+
+impl PrestoTy {
+    /// Types arrow does not have are kept as their presto text form, `time with time zone` is
+    /// normalized to UTC.
+    pub fn arrow_type(&self) -> DataType {
+        use super::PrestoFloat::*;
+        use super::PrestoInt::*;
+        use PrestoTy::*;
+
+        match self {
+            Unknown => DataType::Null,
+            Boolean => DataType::Boolean,
+            PrestoInt(I8) => DataType::Int8,
+            PrestoInt(I16) => DataType::Int16,
+            PrestoInt(I32) => DataType::Int32,
+            PrestoInt(I64) => DataType::Int64,
+            PrestoFloat(F32) => DataType::Float32,
+            PrestoFloat(F64) => DataType::Float64,
+            Decimal(p, s) => DataType::Decimal128(*p as u8, *s as i8),
+            Date => DataType::Date32,
+            Time(p) | TimeWithTimeZone(p) => match time_unit(p) {
+                unit @ (TimeUnit::Second | TimeUnit::Millisecond) => DataType::Time32(unit),
+                unit => DataType::Time64(unit),
+            },
+            Timestamp(p) => DataType::Timestamp(time_unit(p), None),
+            TimestampWithTimeZone(p) => DataType::Timestamp(time_unit(p), Some(TIME_ZONE.into())),
+            IntervalYearToMonth => DataType::Interval(IntervalUnit::YearMonth),
+            IntervalDayToSecond => DataType::Duration(TimeUnit::Millisecond),
+            VarBinary | HyperLogLog | P4HyperLogLog | QDigest(_) | TDigest | SetDigest => {
+                DataType::Binary
+            }
+            Uuid => DataType::FixedSizeBinary(16),
+            Varchar(_)
+            | Char(_)
+            | IpAddress
+            | IpPrefix
+            | Json
+            | Geometry
+            | SphericalGeography
+            | DynDecimal
+            | Other(_, _) => DataType::Utf8,
+            BingTile => DataType::Struct(bing_tile_fields()),
+            Array(ty) => DataType::List(list_field(ty)),
+            Map(k, v) => DataType::Map(map_field(k, v), false),
+            Tuple(_) | Row(_) => DataType::Struct(struct_fields(self)),
+            Option(ty) | Lenient(ty) | Defaulted(ty) => ty.arrow_type(),
+        }
+    }
+}
+
+// timestamps without precision are from old presto versions, which keep milliseconds
+fn time_unit(precision: &Option<usize>) -> TimeUnit {
+    match precision.unwrap_or(3) {
+        0 => TimeUnit::Second,
+        1..=3 => TimeUnit::Millisecond,
+        4..=6 => TimeUnit::Microsecond,
+        _ => TimeUnit::Nanosecond,
+    }
+}
+
+fn list_field(ty: &PrestoTy) -> FieldRef {
+    Arc::new(Field::new_list_field(ty.arrow_type(), true))
+}
+
+fn map_field(k: &PrestoTy, v: &PrestoTy) -> FieldRef {
+    let entries = Fields::from(vec![
+        Field::new("keys", k.arrow_type(), false),
+        Field::new("values", v.arrow_type(), true),
+    ]);
+    Arc::new(Field::new("entries", DataType::Struct(entries), false))
+}
+
+fn struct_fields(ty: &PrestoTy) -> Fields {
+    row_fields(&ty.fields())
+}
+
+fn row_fields(columns: &[(String, PrestoTy)]) -> Fields {
+    columns
+        .iter()
+        .map(|(name, ty)| Field::new(name, ty.arrow_type(), true))
+        .collect()
+}
+
+fn bing_tile_fields() -> Fields {
+    Fields::from(vec![
+        Field::new("x", DataType::UInt32, false),
+        Field::new("y", DataType::UInt32, false),
+        Field::new("zoom", DataType::UInt8, false),
+    ])
+}
+
+impl DataSet<Row> {
+    pub fn arrow_schema(&self) -> Schema {
+        Schema::new(row_fields(self.columns()))
+    }
+
+    pub fn to_record_batch(&self) -> Result<RecordBatch, Error> {
+        self.record_batch(Arc::new(self.arrow_schema()))
+    }
+
+    // the schema is built once per query when reading page by page
+    pub(crate) fn record_batch(&self, schema: SchemaRef) -> Result<RecordBatch, Error> {
+        let rows = self.as_slice();
+        let mut arrays = Vec::with_capacity(self.columns().len());
+        for (idx, (_, ty)) in self.columns().iter().enumerate() {
+            let values = rows
+                .iter()
+                .map(|row| row.values().get(idx).unwrap_or(&PrestoValue::Null))
+                .collect::<Vec<_>>();
+            arrays.push(to_array(ty, &values)?);
+        }
+        RecordBatch::try_new(schema, arrays).map_err(arrow_error)
+    }
+}
+
+fn arrow_error(e: arrow_schema::ArrowError) -> Error {
+    Error::ConvertToArrowFailed(e.to_string())
+}
+
+fn mismatch(ty: &PrestoTy, v: &PrestoValue) -> Error {
+    Error::ConvertToArrowFailed(format!("can not convert {:?} to {}", v, ty.full_type()))
+}
+
+// nulls are kept, other values are converted by `f`, which returns `None` on a mismatch
+fn collect<'v, T>(
+    ty: &PrestoTy,
+    values: &[&'v PrestoValue],
+    f: impl Fn(&'v PrestoValue) -> Option<T>,
+) -> Result<Vec<Option<T>>, Error> {
+    values
+        .iter()
+        .map(|v| match *v {
+            PrestoValue::Null => Ok(None),
+            v => f(v).map(Some).ok_or_else(|| mismatch(ty, v)),
+        })
+        .collect()
+}
+
+fn primitive<T: ArrowPrimitiveType>(
+    ty: &PrestoTy,
+    values: &[&PrestoValue],
+    f: impl Fn(&PrestoValue) -> Option<T::Native>,
+) -> Result<PrimitiveArray<T>, Error> {
+    Ok(collect(ty, values, f)?.into_iter().collect())
+}
+
+fn nulls(values: &[&PrestoValue]) -> Option<NullBuffer> {
+    if values.iter().any(|v| v.is_null()) {
+        Some(values.iter().map(|v| !v.is_null()).collect())
+    } else {
+        None
+    }
+}
+
+fn to_array(ty: &PrestoTy, values: &[&PrestoValue]) -> Result<ArrayRef, Error> {
+    use PrestoValue as V;
+
+    let ret: ArrayRef = match ty {
+        PrestoTy::Unknown => Arc::new(NullArray::new(values.len())),
+        PrestoTy::Boolean => Arc::new(BooleanArray::from(collect(ty, values, |v| match v {
+            V::Boolean(v) => Some(*v),
+            _ => None,
+        })?)),
+        PrestoTy::PrestoInt(PrestoInt::I8) => {
+            Arc::new(primitive::<Int8Type>(ty, values, |v| match v {
+                V::TinyInt(v) => Some(*v),
+                _ => None,
+            })?)
+        }
+        PrestoTy::PrestoInt(PrestoInt::I16) => {
+            Arc::new(primitive::<Int16Type>(ty, values, |v| match v {
+                V::SmallInt(v) => Some(*v),
+                _ => None,
+            })?)
+        }
+        PrestoTy::PrestoInt(PrestoInt::I32) => {
+            Arc::new(primitive::<Int32Type>(ty, values, |v| match v {
+                V::Integer(v) => Some(*v),
+                _ => None,
+            })?)
+        }
+        PrestoTy::PrestoInt(PrestoInt::I64) => {
+            Arc::new(primitive::<Int64Type>(ty, values, |v| match v {
+                V::BigInt(v) => Some(*v),
+                _ => None,
+            })?)
+        }
+        PrestoTy::PrestoFloat(PrestoFloat::F32) => {
+            Arc::new(primitive::<Float32Type>(ty, values, |v| match v {
+                V::Real(v) => Some(*v),
+                _ => None,
+            })?)
+        }
+        PrestoTy::PrestoFloat(PrestoFloat::F64) => {
+            Arc::new(primitive::<Float64Type>(ty, values, |v| match v {
+                V::Double(v) => Some(*v),
+                _ => None,
+            })?)
+        }
+        PrestoTy::Decimal(p, s) => {
+            let data = collect(ty, values, |v| match v {
+                V::Decimal(v) => {
+                    let (n, _) = v
+                        .as_bigdecimal()
+                        .with_scale(*s as i64)
+                        .into_bigint_and_exponent();
+                    n.to_i128()
+                }
+                _ => None,
+            })?;
+            let array = Decimal128Array::from(data)
+                .with_precision_and_scale(*p as u8, *s as i8)
+                .map_err(arrow_error)?;
+            Arc::new(array)
+        }
+        PrestoTy::Date => {
+            let epoch = NaiveDate::from_ymd_opt(1970, 1, 1).unwrap();
+            Arc::new(Date32Array::from(collect(ty, values, |v| match v {
+                V::Date(v) => i32::try_from(v.signed_duration_since(epoch).num_days()).ok(),
+                _ => None,
+            })?))
+        }
+        PrestoTy::Time(p) | PrestoTy::TimeWithTimeZone(p) => {
+            let unit = time_unit(p);
+            let data = collect(ty, values, |v| match v {
+                V::Time(v) => Some(time_value(v.to_chrono(), &unit)),
+                V::TimeWithTimeZone(v) => Some(time_value(v.to_utc(), &unit)),
+                _ => None,
+            })?;
+            match unit {
+                TimeUnit::Second => {
+                    Arc::new(PrimitiveArray::<Time32SecondType>::from(narrow(ty, data)?))
+                }
+                TimeUnit::Millisecond => Arc::new(PrimitiveArray::<Time32MillisecondType>::from(
+                    narrow(ty, data)?,
+                )),
+                TimeUnit::Microsecond => {
+                    Arc::new(PrimitiveArray::<Time64MicrosecondType>::from(data))
+                }
+                TimeUnit::Nanosecond => {
+                    Arc::new(PrimitiveArray::<Time64NanosecondType>::from(data))
+                }
+            }
+        }
+        PrestoTy::Timestamp(p) | PrestoTy::TimestampWithTimeZone(p) => {
+            let unit = time_unit(p);
+            let data = collect(ty, values, |v| match v {
+                V::Timestamp(v) => timestamp_value(v.to_chrono(), &unit),
+                V::TimestampWithTimeZone(v) => {
+                    timestamp_value(v.to_date_time()?.naive_utc(), &unit)
+                }
+                _ => None,
+            })?;
+            let tz = match ty {
+                PrestoTy::TimestampWithTimeZone(_) => Some(TIME_ZONE),
+                _ => None,
+            };
+            match unit {
+                TimeUnit::Second => timestamp_array::<TimestampSecondType>(data, tz),
+                TimeUnit::Millisecond => timestamp_array::<TimestampMillisecondType>(data, tz),
+                TimeUnit::Microsecond => timestamp_array::<TimestampMicrosecondType>(data, tz),
+                TimeUnit::Nanosecond => timestamp_array::<TimestampNanosecondType>(data, tz),
+            }
+        }
+        PrestoTy::IntervalYearToMonth => Arc::new(primitive::<IntervalYearMonthType>(
+            ty,
+            values,
+            |v| match v {
+                V::IntervalYearToMonth(v) => i32::try_from(v.total_months()).ok(),
+                _ => None,
+            },
+        )?),
+        PrestoTy::IntervalDayToSecond => Arc::new(primitive::<DurationMillisecondType>(
+            ty,
+            values,
+            |v| match v {
+                V::IntervalDayToSecond(v) => v.total_milliseconds().ok(),
+                _ => None,
+            },
+        )?),
+        PrestoTy::VarBinary
+        | PrestoTy::HyperLogLog
+        | PrestoTy::P4HyperLogLog
+        | PrestoTy::QDigest(_)
+        | PrestoTy::TDigest
+        | PrestoTy::SetDigest => Arc::new(BinaryArray::from(collect(ty, values, |v| match v {
+            V::VarBinary(v) => Some(&v[..]),
+            V::HyperLogLog(v) => Some(v.as_bytes()),
+            V::P4HyperLogLog(v) => Some(v.as_bytes()),
+            V::QDigest(v) => Some(v.as_bytes()),
+            V::TDigest(v) => Some(v.as_bytes()),
+            V::SetDigest(v) => Some(v.as_bytes()),
+            _ => None,
+        })?)),
+        PrestoTy::Uuid => {
+            let data = collect(ty, values, |v| match v {
+                V::Uuid(v) => Some(*v.as_bytes()),
+                _ => None,
+            })?;
+            let array = FixedSizeBinaryArray::try_from_sparse_iter_with_size(data.into_iter(), 16)
+                .map_err(arrow_error)?;
+            Arc::new(array)
+        }
+        PrestoTy::BingTile => {
+            let tiles = collect(ty, values, |v| match v {
+                V::BingTile(v) => Some(v),
+                _ => None,
+            })?;
+            let x = tiles
+                .iter()
+                .map(|t| t.map_or(0, |t| t.x()))
+                .collect::<Vec<_>>();
+            let y = tiles
+                .iter()
+                .map(|t| t.map_or(0, |t| t.y()))
+                .collect::<Vec<_>>();
+            let zoom = tiles
+                .iter()
+                .map(|t| t.map_or(0, |t| t.zoom()))
+                .collect::<Vec<_>>();
+            let arrays: Vec<ArrayRef> = vec![
+                Arc::new(PrimitiveArray::<UInt32Type>::from(x)),
+                Arc::new(PrimitiveArray::<UInt32Type>::from(y)),
+                Arc::new(PrimitiveArray::<UInt8Type>::from(zoom)),
+            ];
+            let array = StructArray::try_new(bing_tile_fields(), arrays, nulls(values))
+                .map_err(arrow_error)?;
+            Arc::new(array)
+        }
+        PrestoTy::Array(inner) => {
+            let mut lengths = Vec::with_capacity(values.len());
+            let mut items = vec![];
+            for v in values {
+                match v {
+                    V::Null => lengths.push(0),
+                    V::Array(v) => {
+                        lengths.push(v.len());
+                        items.extend(v.iter());
+                    }
+                    v => return Err(mismatch(ty, v)),
+                }
+            }
+            let array = ListArray::try_new(
+                list_field(inner),
+                OffsetBuffer::from_lengths(lengths),
+                to_array(inner, &items)?,
+                nulls(values),
+            )
+            .map_err(arrow_error)?;
+            Arc::new(array)
+        }
+        PrestoTy::Map(k, v) => {
+            let mut lengths = Vec::with_capacity(values.len());
+            let mut keys = vec![];
+            let mut vals = vec![];
+            for value in values {
+                match value {
+                    V::Null => lengths.push(0),
+                    V::Map(entries) => {
+                        lengths.push(entries.len());
+                        for (key, val) in entries {
+                            keys.push(key);
+                            vals.push(val);
+                        }
+                    }
+                    value => return Err(mismatch(ty, value)),
+                }
+            }
+            let field = map_field(k, v);
+            let fields = match field.data_type() {
+                DataType::Struct(fields) => fields.clone(),
+                _ => unreachable!(),
+            };
+            let entries =
+                StructArray::try_new(fields, vec![to_array(k, &keys)?, to_array(v, &vals)?], None)
+                    .map_err(arrow_error)?;
+            let array = MapArray::try_new(
+                field,
+                OffsetBuffer::from_lengths(lengths),
+                entries,
+                nulls(values),
+                false,
+            )
+            .map_err(arrow_error)?;
+            Arc::new(array)
+        }
+        PrestoTy::Tuple(_) | PrestoTy::Row(_) => {
+            let columns = ty.fields();
+            let mut arrays = Vec::with_capacity(columns.len());
+            for (idx, (_, field_ty)) in columns.iter().enumerate() {
+                let fields = values
+                    .iter()
+                    .map(|v| match v {
+                        V::Null => Ok(&PrestoValue::Null),
+                        V::Row(row) => row.values().get(idx).ok_or_else(|| mismatch(ty, v)),
+                        v => Err(mismatch(ty, v)),
+                    })
+                    .collect::<Result<Vec<_>, _>>()?;
+                arrays.push(to_array(field_ty, &fields)?);
+            }
+            let array = StructArray::try_new(row_fields(&columns), arrays, nulls(values))
+                .map_err(arrow_error)?;
+            Arc::new(array)
+        }
+        PrestoTy::Option(inner) | PrestoTy::Lenient(inner) | PrestoTy::Defaulted(inner) => {
+            to_array(inner, values)?
+        }
+        _ => Arc::new(StringArray::from(collect(ty, values, |v| {
+            Some(v.to_text())
+        })?)),
+    };
+    Ok(ret)
+}
+
+fn time_value(v: NaiveTime, unit: &TimeUnit) -> i64 {
+    let seconds = v.num_seconds_from_midnight() as i64;
+    let nanos = v.nanosecond() as i64;
+    match unit {
+        TimeUnit::Second => seconds,
+        TimeUnit::Millisecond => seconds * 1_000 + nanos / 1_000_000,
+        TimeUnit::Microsecond => seconds * 1_000_000 + nanos / 1_000,
+        TimeUnit::Nanosecond => seconds * 1_000_000_000 + nanos,
+    }
+}
+
+fn narrow(ty: &PrestoTy, data: Vec<Option<i64>>) -> Result<Vec<Option<i32>>, Error> {
+    data.into_iter()
+        .map(|v| {
+            v.map(i32::try_from)
+                .transpose()
+                .map_err(|e| Error::ConvertToArrowFailed(format!("{} {}", ty.full_type(), e)))
+        })
+        .collect()
+}
+
+fn timestamp_value(v: NaiveDateTime, unit: &TimeUnit) -> Option<i64> {
+    let v = v.and_utc();
+    match unit {
+        TimeUnit::Second => Some(v.timestamp()),
+        TimeUnit::Millisecond => Some(v.timestamp_millis()),
+        TimeUnit::Microsecond => Some(v.timestamp_micros()),
+        TimeUnit::Nanosecond => v.timestamp_nanos_opt(),
+    }
+}
+
+fn timestamp_array<T>(data: Vec<Option<i64>>, tz: Option<&str>) -> ArrayRef
+where
+    T: ArrowTimestampType,
+{
+    let array = data.into_iter().collect::<PrimitiveArray<T>>();
+    match tz {
+        Some(tz) => Arc::new(array.with_timezone(tz)),
+        None => Arc::new(array),
+    }
+}
@@ -0,0 +1,150 @@
+use std::fmt;
+use std::ops::Deref;
+
+use base64::engine::general_purpose::STANDARD;
+use base64::Engine;
+use bytes::Bytes;
+use serde::de::{self, DeserializeSeed, Deserializer, Visitor};
+
+use super::{Context, Presto, PrestoMapKey, PrestoTy, SqlLiteral};
+
+/// `varbinary` value, presto transfers it as a base64 encoded string.
+///
+/// `Vec<u8>` can not be used directly, because it is already mapped to `array(tinyint)`.
+#[derive(Debug, Default, Eq, PartialEq, Hash, Ord, PartialOrd, Clone)]
+pub struct Binary {
+    inner: Vec<u8>,
+}
+
+impl Binary {
+    pub fn new(inner: Vec<u8>) -> Self {
+        Binary { inner }
+    }
+
+    pub fn into_vec(self) -> Vec<u8> {
+        self.inner
+    }
+}
+
+impl From<Vec<u8>> for Binary {
+    fn from(inner: Vec<u8>) -> Self {
+        Binary { inner }
+    }
+}
+
+impl From<Binary> for Vec<u8> {
+    fn from(b: Binary) -> Self {
+        b.inner
+    }
+}
+
+impl Deref for Binary {
+    type Target = [u8];
+
+    fn deref(&self) -> &[u8] {
+        &self.inner
+    }
+}
+
+impl AsRef<[u8]> for Binary {
+    fn as_ref(&self) -> &[u8] {
+        &self.inner
+    }
+}
+
+macro_rules! gen_binary {
+    ($ty:ty, $seed:ident, $from:expr) => {
+        impl Presto for $ty {
+            type ValueType<'a> = String;
+            type Seed<'a, 'de> = $seed;
+
+            fn value(&self) -> Self::ValueType<'_> {
+                encode_base64(self)
+            }
+
+            fn ty() -> PrestoTy {
+                PrestoTy::VarBinary
+            }
+
+            fn seed<'a, 'de>(_ctx: &'a Context) -> Self::Seed<'a, 'de> {
+                $seed
+            }
+
+            fn empty() -> Self {
+                Default::default()
+            }
+        }
+
+        impl PrestoMapKey for $ty {}
+
+        impl SqlLiteral for $ty {
+            fn sql_literal(&self) -> String {
+                hex_literal(self)
+            }
+        }
+
+        pub struct $seed;
+
+        impl<'de> Visitor<'de> for $seed {
+            type Value = $ty;
+
+            fn expecting(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+                formatter.write_str("base64 encoded varbinary")
+            }
+
+            fn visit_str<E>(self, v: &str) -> Result<Self::Value, E>
+            where
+                E: de::Error,
+            {
+                let data = decode_base64(v).map_err(|e| {
+                    de::Error::custom(format!("deserialize varbinary failed, reason: {}", e))
+                })?;
+                Ok($from(data))
+            }
+        }
+
+        impl<'de> DeserializeSeed<'de> for $seed {
+            type Value = $ty;
+            fn deserialize<D>(self, deserializer: D) -> Result<Self::Value, D::Error>
+            where
+                D: Deserializer<'de>,
+            {
+                deserializer.deserialize_str(self)
+            }
+        }
+    };
+}
+
+gen_binary!(Binary, BinarySeed, Binary::new);
+gen_binary!(Bytes, BytesSeed, Bytes::from);
+
+pub(super) fn encode_base64(data: &[u8]) -> String {
+    STANDARD.encode(data)
+}
+
+pub(super) fn decode_base64(data: &str) -> Result<Vec<u8>, base64::DecodeError> {
+    STANDARD.decode(data)
+}
+
+/// render bytes as a varbinary literal, e.g. `X'00FF'`
+pub(super) fn hex_literal(data: &[u8]) -> String {
+    let mut ret = String::with_capacity(data.len() * 2 + 3);
+    ret.push_str("X'");
+    for b in data {
+        ret.push_str(&format!("{:02X}", b));
+    }
+    ret.push('\'');
+    ret
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_literal() {
+        let b = Binary::new(vec![0, 15, 255]);
+        assert_eq!(b.sql_literal(), "X'000FFF'");
+        assert_eq!(Binary::default().sql_literal(), "X''");
+    }
+}
@@ -0,0 +1,84 @@
+use serde::de::{self, DeserializeSeed, Deserializer};
+use serde::{Deserialize, Serialize};
+
+use super::{Context, Error, Presto, PrestoTy, SqlLiteral};
+
+/// max zoom level of a bing tile
+pub const MAX_ZOOM_LEVEL: u8 = 23;
+
+/// A bing tile, presto sends it as an object of its coordinates and zoom level.
+#[derive(Debug, Default, Eq, PartialEq, Hash, Clone, Copy, Serialize, Deserialize)]
+pub struct BingTile {
+    x: u32,
+    y: u32,
+    zoom: u8,
+}
+
+impl BingTile {
+    pub fn new(x: u32, y: u32, zoom: u8) -> Result<Self, Error> {
+        if zoom > MAX_ZOOM_LEVEL {
+            return Err(Error::InvalidBingTile(format!(
+                "zoom level {} is greater than {}",
+                zoom, MAX_ZOOM_LEVEL
+            )));
+        }
+        let max = 1u32 << zoom;
+        if x >= max || y >= max {
+            return Err(Error::InvalidBingTile(format!(
+                "({}, {}) is out of range for zoom level {}",
+                x, y, zoom
+            )));
+        }
+        Ok(BingTile { x, y, zoom })
+    }
+
+    pub fn x(&self) -> u32 {
+        self.x
+    }
+
+    pub fn y(&self) -> u32 {
+        self.y
+    }
+
+    pub fn zoom(&self) -> u8 {
+        self.zoom
+    }
+}
+
+impl Presto for BingTile {
+    type ValueType<'a> = &'a BingTile;
+    type Seed<'a, 'de> = BingTileSeed;
+
+    fn value(&self) -> Self::ValueType<'_> {
+        self
+    }
+    fn ty() -> PrestoTy {
+        PrestoTy::BingTile
+    }
+    fn seed<'a, 'de>(_ctx: &'a Context) -> Self::Seed<'a, 'de> {
+        BingTileSeed
+    }
+
+    fn empty() -> Self {
+        Default::default()
+    }
+}
+
+impl SqlLiteral for BingTile {
+    fn sql_literal(&self) -> String {
+        format!("bing_tile({}, {}, {})", self.x, self.y, self.zoom)
+    }
+}
+
+pub struct BingTileSeed;
+
+impl<'de> DeserializeSeed<'de> for BingTileSeed {
+    type Value = BingTile;
+    fn deserialize<D>(self, deserializer: D) -> Result<Self::Value, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        let t = BingTile::deserialize(deserializer)?;
+        BingTile::new(t.x, t.y, t.zoom).map_err(de::Error::custom)
+    }
+}
@@ -0,0 +1,243 @@
+use std::borrow::Cow;
+use std::fmt;
+use std::marker::PhantomData;
+
+use serde::de::{self, Deserialize, DeserializeSeed, Deserializer, SeqAccess, Visitor};
+use serde_json::value::RawValue as JsonRawValue;
+
+use super::{Context, Presto, PrestoTy};
+
+/// Types which are decoded from data borrowed for `'de`. Every `Presto` type is one, besides
+/// `&'de str`, `Cow<'de, str>` and `&'de serde_json::value::RawValue` borrow from the data
+/// being decoded, see `Page::rows`.
+pub trait PrestoBorrow<'de>: Sized {
+    type Seed<'a>: DeserializeSeed<'de, Value = Self>;
+
+    fn ty() -> PrestoTy;
+
+    /// caller must provide a valid context
+    fn seed<'a>(ctx: &'a Context<'a>) -> Self::Seed<'a>;
+}
+
+impl<'de, T: Presto> PrestoBorrow<'de> for T {
+    type Seed<'a> = T::Seed<'a, 'de>;
+
+    fn ty() -> PrestoTy {
+        T::ty()
+    }
+
+    fn seed<'a>(ctx: &'a Context<'a>) -> Self::Seed<'a> {
+        T::seed(ctx)
+    }
+}
+
+// borrowed types can not be `Presto`, so their options and arrays are implemented one by one
+macro_rules! gen_borrowed {
+    ($ty:ty, $seed:ident, $pty:expr) => {
+        impl<'de> PrestoBorrow<'de> for $ty {
+            type Seed<'a> = $seed;
+
+            fn ty() -> PrestoTy {
+                $pty
+            }
+
+            fn seed<'a>(_ctx: &'a Context<'a>) -> Self::Seed<'a> {
+                $seed
+            }
+        }
+
+        impl<'de> PrestoBorrow<'de> for Option<$ty> {
+            type Seed<'a> = BorrowedOptionSeed<'a, $ty>;
+
+            fn ty() -> PrestoTy {
+                PrestoTy::Option(Box::new($pty))
+            }
+
+            fn seed<'a>(ctx: &'a Context<'a>) -> Self::Seed<'a> {
+                BorrowedOptionSeed {
+                    ctx,
+                    _marker: PhantomData,
+                }
+            }
+        }
+
+        impl<'de> PrestoBorrow<'de> for Vec<$ty> {
+            type Seed<'a> = BorrowedVecSeed<'a, $ty>;
+
+            fn ty() -> PrestoTy {
+                PrestoTy::Array(Box::new($pty))
+            }
+
+            fn seed<'a>(ctx: &'a Context<'a>) -> Self::Seed<'a> {
+                BorrowedVecSeed {
+                    ctx,
+                    _marker: PhantomData,
+                }
+            }
+        }
+    };
+}
+
+gen_borrowed!(&'de str, StrSeed, PrestoTy::Varchar(None));
+gen_borrowed!(Cow<'de, str>, CowStrSeed, PrestoTy::Varchar(None));
+gen_borrowed!(&'de JsonRawValue, JsonRawValueSeed, PrestoTy::Unknown);
+
+/// Decode `&str`, strings with escapes can not be borrowed, use `Cow<str>` for them.
+pub struct StrSeed;
+
+impl<'de> Visitor<'de> for StrSeed {
+    type Value = &'de str;
+    fn expecting(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+        formatter.write_str("a borrowed string")
+    }
+
+    fn visit_borrowed_str<E>(self, v: &'de str) -> Result<Self::Value, E>
+    where
+        E: de::Error,
+    {
+        Ok(v)
+    }
+}
+
+impl<'de> DeserializeSeed<'de> for StrSeed {
+    type Value = &'de str;
+    fn deserialize<D>(self, deserializer: D) -> Result<Self::Value, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        deserializer.deserialize_str(self)
+    }
+}
+
+pub struct CowStrSeed;
+
+impl<'de> Visitor<'de> for CowStrSeed {
+    type Value = Cow<'de, str>;
+    fn expecting(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+        formatter.write_str("a string")
+    }
+
+    fn visit_borrowed_str<E>(self, v: &'de str) -> Result<Self::Value, E>
+    where
+        E: de::Error,
+    {
+        Ok(Cow::Borrowed(v))
+    }
+
+    fn visit_str<E>(self, v: &str) -> Result<Self::Value, E>
+    where
+        E: de::Error,
+    {
+        Ok(Cow::Owned(v.to_string()))
+    }
+
+    fn visit_string<E>(self, v: String) -> Result<Self::Value, E>
+    where
+        E: de::Error,
+    {
+        Ok(Cow::Owned(v))
+    }
+}
+
+impl<'de> DeserializeSeed<'de> for CowStrSeed {
+    type Value = Cow<'de, str>;
+    fn deserialize<D>(self, deserializer: D) -> Result<Self::Value, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        deserializer.deserialize_str(self)
+    }
+}
+
+pub struct JsonRawValueSeed;
+
+impl<'de> DeserializeSeed<'de> for JsonRawValueSeed {
+    type Value = &'de JsonRawValue;
+    fn deserialize<D>(self, deserializer: D) -> Result<Self::Value, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        <&'de JsonRawValue>::deserialize(deserializer)
+    }
+}
+
+pub struct BorrowedOptionSeed<'a, T> {
+    ctx: &'a Context<'a>,
+    _marker: PhantomData<T>,
+}
+
+impl<'a, 'de, T: PrestoBorrow<'de>> Visitor<'de> for BorrowedOptionSeed<'a, T> {
+    type Value = Option<T>;
+    fn expecting(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+        formatter.write_str(&T::ty().raw_type().to_str())
+    }
+
+    fn visit_none<E>(self) -> Result<Self::Value, E>
+    where
+        E: de::Error,
+    {
+        Ok(None)
+    }
+
+    fn visit_unit<E>(self) -> Result<Self::Value, E>
+    where
+        E: de::Error,
+    {
+        Ok(None)
+    }
+
+    fn visit_some<D>(self, deserializer: D) -> Result<Self::Value, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        T::seed(self.ctx).deserialize(deserializer).map(Some)
+    }
+}
+
+impl<'a, 'de, T: PrestoBorrow<'de>> DeserializeSeed<'de> for BorrowedOptionSeed<'a, T> {
+    type Value = Option<T>;
+    fn deserialize<D>(self, deserializer: D) -> Result<Self::Value, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        deserializer.deserialize_option(self)
+    }
+}
+
+pub struct BorrowedVecSeed<'a, T> {
+    ctx: &'a Context<'a>,
+    _marker: PhantomData<T>,
+}
+
+impl<'a, 'de, T: PrestoBorrow<'de>> Visitor<'de> for BorrowedVecSeed<'a, T> {
+    type Value = Vec<T>;
+    fn expecting(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+        formatter.write_str("sequence of same presto type")
+    }
+
+    fn visit_seq<A>(self, mut seq: A) -> Result<Self::Value, A::Error>
+    where
+        A: SeqAccess<'de>,
+    {
+        let ty = match self.ctx.ty() {
+            PrestoTy::Array(ty) => ty,
+            _ => panic!("invalid context"),
+        };
+        let ctx = self.ctx.with_ty(ty);
+        let mut ret = vec![];
+        while let Some(d) = seq.next_element_seed(T::seed(&ctx))? {
+            ret.push(d);
+        }
+        Ok(ret)
+    }
+}
+
+impl<'a, 'de, T: PrestoBorrow<'de>> DeserializeSeed<'de> for BorrowedVecSeed<'a, T> {
+    type Value = Vec<T>;
+    fn deserialize<D>(self, deserializer: D) -> Result<Self::Value, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        deserializer.deserialize_seq(self)
+    }
+}
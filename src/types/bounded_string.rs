@@ -0,0 +1,103 @@
+use std::str::FromStr;
+
+use serde::de::{self, Deserialize, DeserializeSeed, Deserializer};
+
+use super::{Context, Error, Presto, PrestoMapKey, PrestoTy};
+
+/// `varchar(N)`, strings longer than `N` characters can not be constructed or decoded.
+#[derive(Debug, Default, Eq, PartialEq, Hash, Clone)]
+pub struct BoundedString<const N: usize> {
+    inner: String,
+}
+
+impl<const N: usize> BoundedString<N> {
+    pub fn new(inner: String) -> Result<Self, Error> {
+        check_length(&inner, N)?;
+        Ok(BoundedString { inner })
+    }
+
+    pub fn as_str(&self) -> &str {
+        &self.inner
+    }
+
+    pub fn into_string(self) -> String {
+        self.inner
+    }
+}
+
+impl<const N: usize> FromStr for BoundedString<N> {
+    type Err = Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Self::new(s.to_string())
+    }
+}
+
+impl<const N: usize> TryFrom<String> for BoundedString<N> {
+    type Error = Error;
+
+    fn try_from(s: String) -> Result<Self, Self::Error> {
+        Self::new(s)
+    }
+}
+
+impl<const N: usize> Presto for BoundedString<N> {
+    type ValueType<'a> = &'a str;
+    type Seed<'a, 'de> = BoundedStringSeed<N>;
+
+    fn value(&self) -> Self::ValueType<'_> {
+        &self.inner
+    }
+    fn ty() -> PrestoTy {
+        PrestoTy::Varchar(Some(N))
+    }
+    fn seed<'a, 'de>(_ctx: &'a Context) -> Self::Seed<'a, 'de> {
+        BoundedStringSeed
+    }
+
+    fn empty() -> Self {
+        Default::default()
+    }
+}
+
+impl<const N: usize> PrestoMapKey for BoundedString<N> {}
+
+pub struct BoundedStringSeed<const N: usize>;
+
+impl<'de, const N: usize> DeserializeSeed<'de> for BoundedStringSeed<N> {
+    type Value = BoundedString<N>;
+    fn deserialize<D>(self, deserializer: D) -> Result<Self::Value, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        let s = String::deserialize(deserializer)?;
+        BoundedString::new(s).map_err(de::Error::custom)
+    }
+}
+
+fn check_length(s: &str, n: usize) -> Result<(), Error> {
+    let len = s.chars().count();
+    if len > n {
+        Err(Error::InvalidLength(format!(
+            "{} characters do not fit in varchar({})",
+            len, n
+        )))
+    } else {
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_length() {
+        let s = BoundedString::<3>::from_str("abc").unwrap();
+        assert_eq!(s.value(), "abc");
+        assert_eq!(BoundedString::<3>::ty().full_type(), "varchar(3)");
+
+        assert!(BoundedString::<2>::from_str("abc").is_err());
+        assert!(BoundedString::<2>::from_str("日本").is_ok());
+    }
+}
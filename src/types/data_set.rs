@@ -1,13 +1,14 @@
 use std::fmt;
 use std::marker::PhantomData;
+use std::sync::Arc;
 
 use iterable::Iterable;
 use serde::de::{self, Deserializer, MapAccess, Visitor};
 use serde::ser::{SerializeStruct, Serializer};
 use serde::{Deserialize, Serialize};
 
-use super::util::SerializeIterator;
-use super::{Context, Error, Presto, PrestoTy, VecSeed};
+use super::util::{column_index, SerializeIterator};
+use super::{resolve_row_params, Context, Error, Presto, PrestoTy, VecSeed};
 use crate::models::Column;
 use crate::Row;
 
@@ -19,7 +20,11 @@ pub struct DataSet<T: Presto> {
 
 impl<T: Presto> DataSet<T> {
     pub fn new(data: Vec<T>) -> Result<Self, Error> {
-        let types = match T::ty() {
+        let ty = match T::ty() {
+            PrestoTy::Lenient(ty) => *ty,
+            ty => ty,
+        };
+        let types = match ty {
             PrestoTy::Row(r) => {
                 if r.is_empty() {
                     return Err(Error::EmptyInPrestoRow);
@@ -27,12 +32,27 @@ impl<T: Presto> DataSet<T> {
                     r
                 }
             }
+            // tuples are positional, presto names such columns `_colN`
+            PrestoTy::Tuple(t) => t
+                .into_iter()
+                .enumerate()
+                .map(|(i, ty)| (format!("_col{}", i), ty))
+                .collect(),
             _ => return Err(Error::NonePrestoRow),
         };
 
         Ok(DataSet { types, data })
     }
 
+    pub fn columns(&self) -> &[(String, PrestoTy)] {
+        &self.types
+    }
+
+    /// names are matched case insensitively when there is no exact match
+    pub fn column_index(&self, name: &str) -> Option<usize> {
+        column_index(&self.types, name)
+    }
+
     pub fn split(self) -> (Vec<(String, PrestoTy)>, Vec<T>) {
         (self.types, self.data)
     }
@@ -65,6 +85,36 @@ impl DataSet<Row> {
         }
         Ok(DataSet { types, data })
     }
+
+    /// values of a column decoded as `T`
+    pub fn column<T: Presto>(&self, name: &str) -> Result<Vec<T>, Error> {
+        let idx = self.index(name)?;
+        self.data.iter().map(|row| row.get(idx)).collect()
+    }
+
+    /// keep the given columns in the given order
+    pub fn project(&self, names: &[&str]) -> Result<DataSet<Row>, Error> {
+        let idxs = names
+            .iter()
+            .map(|name| self.index(name))
+            .collect::<Result<Vec<_>, _>>()?;
+        let types: Vec<_> = idxs.iter().map(|idx| self.types[*idx].clone()).collect();
+        let columns: Arc<[_]> = types.clone().into();
+        let data = self
+            .data
+            .iter()
+            .map(|row| {
+                let values = idxs.iter().map(|idx| row.values()[*idx].clone()).collect();
+                Row::new(values, columns.clone())
+            })
+            .collect();
+        DataSet::new_row(types, data)
+    }
+
+    fn index(&self, name: &str) -> Result<usize, Error> {
+        self.column_index(name)
+            .ok_or_else(|| Error::ColumnNotFound(name.to_string()))
+    }
 }
 
 impl<T: Presto + Clone> Clone for DataSet<T> {
@@ -159,12 +209,19 @@ impl<'de, T: Presto> Deserialize<'de> for DataSet<T> {
                     None => {}
                 }
 
-                if let PrestoTy::Unknown = T::ty() {
+                // tuples do not know the column names, keep the provided ones
+                let ty = match T::ty() {
+                    PrestoTy::Lenient(ty) => *ty,
+                    ty => ty,
+                };
+                if let PrestoTy::Unknown | PrestoTy::Tuple(_) = ty {
                     Ok(DataSet { types, data })
                 } else {
-                    DataSet::new(data).map_err(|e| {
+                    let mut data_set = DataSet::new(data).map_err(|e| {
                         de::Error::custom(format!("construct data failed, reason: {}", e))
-                    })
+                    })?;
+                    data_set.types = resolve_row_params(data_set.types, &types);
+                    Ok(data_set)
                 }
             }
         }
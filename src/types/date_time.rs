@@ -1,19 +1,22 @@
-use chrono::{DateTime, FixedOffset};
+use chrono::Timelike;
 use std::fmt;
 
 use chrono::naive::{NaiveDate, NaiveDateTime, NaiveTime};
 use serde::de::{self, DeserializeSeed, Deserializer, Visitor};
 
-use super::{Context, Presto, PrestoTy};
+use super::{Context, Presto, PrestoTy, SqlLiteral};
+
+// digits of the fraction of a second chrono keeps
+pub(super) const NANO_PRECISION: usize = 9;
 
 macro_rules! gen_date_time {
-    ($ty:ty, $seed:ident, $pty:expr, $format:expr, $empty:expr, $expect:expr) => {
+    ($ty:ty, $seed:ident, $pty:expr, $format:expr, $value:expr, $literal:expr, $empty:expr, $expect:expr) => {
         impl Presto for $ty {
             type ValueType<'a> = String;
             type Seed<'a, 'de> = $seed;
 
             fn value(&self) -> Self::ValueType<'_> {
-                self.format($format).to_string()
+                $value(self)
             }
 
             fn ty() -> PrestoTy {
@@ -29,6 +32,12 @@ macro_rules! gen_date_time {
             }
         }
 
+        impl SqlLiteral for $ty {
+            fn sql_literal(&self) -> String {
+                format!("{} '{}'", $literal, self.value())
+            }
+        }
+
         pub struct $seed;
 
         impl<'de> Visitor<'de> for $seed {
@@ -60,19 +69,29 @@ macro_rules! gen_date_time {
     };
 }
 
+// chrono types keep at most nanoseconds, wider columns are only accepted through `Lenient`
+// and the extra digits are truncated.
 gen_date_time!(
     NaiveDate,
     NaiveDateSeed,
     PrestoTy::Date,
     "%Y-%m-%d",
+    |v: &NaiveDate| v.format("%Y-%m-%d").to_string(),
+    "DATE",
     NaiveDate::from_ymd_opt(1970, 1, 1).unwrap(),
     "naive date"
 );
 gen_date_time!(
     NaiveDateTime,
     NaiveDateTimeSeed,
-    PrestoTy::Timestamp,
-    "%Y-%m-%d %H:%M:%S%.3f",
+    PrestoTy::Timestamp(Some(NANO_PRECISION)),
+    "%Y-%m-%d %H:%M:%S%.f",
+    |v: &NaiveDateTime| format!(
+        "{}{}",
+        v.format("%Y-%m-%d %H:%M:%S"),
+        nano_fraction(v.nanosecond())
+    ),
+    "TIMESTAMP",
     NaiveDate::from_ymd_opt(1970, 1, 1)
         .unwrap()
         .and_hms_opt(0, 0, 0)
@@ -82,20 +101,108 @@ gen_date_time!(
 gen_date_time!(
     NaiveTime,
     NaiveTimeSeed,
-    PrestoTy::Time,
-    "%H:%M:%S%.3f",
+    PrestoTy::Time(Some(NANO_PRECISION)),
+    "%H:%M:%S%.f",
+    |v: &NaiveTime| format!("{}{}", v.format("%H:%M:%S"), nano_fraction(v.nanosecond())),
+    "TIME",
     NaiveTime::from_hms_opt(0, 0, 0).unwrap(),
     "naive date time"
 );
-gen_date_time!(
-    DateTime<FixedOffset>,
-    DateTimeWithZoneSeed,
-    PrestoTy::TimestampWithTimeZone,
-    "%Y-%m-%d %H:%M:%S%.3f %:z",
-    DateTime::parse_from_str(
-        "1970-01-01 00:00:00.000 +00:00",
-        "%Y-%m-%d %H:%M:%S%.3f %:z"
-    )
-    .unwrap(),
-    "date time with time zone"
-);
+// implement `Presto` for types of other crates by converting them from and to a type of this
+// crate, so they share the same wire format, precision handling and sql literal.
+#[allow(unused_macros)]
+macro_rules! gen_proxy {
+    ($ty:ty, $seed:ident, $proxy:ty, $proxy_seed:expr, $to_proxy:expr, $from_proxy:expr, $expect:expr) => {
+        impl $crate::types::Presto for $ty {
+            type ValueType<'a> = String;
+            type Seed<'a, 'de> = $seed;
+
+            fn value(&self) -> Self::ValueType<'_> {
+                $crate::types::Presto::value(&$to_proxy(self))
+            }
+
+            fn ty() -> $crate::types::PrestoTy {
+                $crate::types::date_time::nano_precision(<$proxy as $crate::types::Presto>::ty())
+            }
+
+            fn seed<'a, 'de>(_ctx: &'a $crate::types::Context) -> Self::Seed<'a, 'de> {
+                $seed
+            }
+
+            fn empty() -> Self {
+                $from_proxy(<$proxy as $crate::types::Presto>::empty()).unwrap()
+            }
+        }
+
+        impl $crate::types::SqlLiteral for $ty {
+            fn sql_literal(&self) -> String {
+                $crate::types::SqlLiteral::sql_literal(&$to_proxy(self))
+            }
+        }
+
+        pub struct $seed;
+
+        impl<'de> ::serde::de::DeserializeSeed<'de> for $seed {
+            type Value = $ty;
+            fn deserialize<D>(self, deserializer: D) -> Result<Self::Value, D::Error>
+            where
+                D: ::serde::de::Deserializer<'de>,
+            {
+                let v = ::serde::de::DeserializeSeed::deserialize($proxy_seed, deserializer)?;
+                $from_proxy(v).map_err(|e| {
+                    <D::Error as ::serde::de::Error>::custom(format!(
+                        "deserialize {} failed, reason: {}",
+                        $expect, e
+                    ))
+                })
+            }
+        }
+    };
+}
+
+// the other crates keep at most nanoseconds, even when proxied by a picosecond type
+pub(super) fn nano_precision(ty: PrestoTy) -> PrestoTy {
+    use PrestoTy::*;
+
+    match ty {
+        Time(_) => Time(Some(NANO_PRECISION)),
+        TimeWithTimeZone(_) => TimeWithTimeZone(Some(NANO_PRECISION)),
+        Timestamp(_) => Timestamp(Some(NANO_PRECISION)),
+        TimestampWithTimeZone(_) => TimestampWithTimeZone(Some(NANO_PRECISION)),
+        ty => ty,
+    }
+}
+
+/// format the fraction of a second with 3, 6 or 9 digits, whichever is the shortest
+/// lossless one.
+// `is_multiple_of` is only stable since rust 1.87
+#[allow(clippy::manual_is_multiple_of)]
+pub(super) fn nano_fraction(nanos: u32) -> String {
+    if nanos % 1_000_000 == 0 {
+        format!(".{:03}", nanos / 1_000_000)
+    } else if nanos % 1_000 == 0 {
+        format!(".{:06}", nanos / 1_000)
+    } else {
+        format!(".{:09}", nanos)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_precision() {
+        let s = "2001-08-22 03:04:05.123456789";
+        let d = NaiveDateTime::parse_from_str(s, "%Y-%m-%d %H:%M:%S%.f").unwrap();
+        assert_eq!(d.value(), s);
+        assert_eq!(d.sql_literal(), format!("TIMESTAMP '{}'", s));
+
+        let d =
+            NaiveDateTime::parse_from_str("2001-08-22 03:04:05", "%Y-%m-%d %H:%M:%S%.f").unwrap();
+        assert_eq!(d.value(), "2001-08-22 03:04:05.000");
+
+        let t = NaiveTime::parse_from_str("01:02:03.123456", "%H:%M:%S%.f").unwrap();
+        assert_eq!(t.value(), "01:02:03.123456");
+    }
+}
@@ -1,7 +1,8 @@
 use std::str::FromStr;
 
-use serde::de::{self, Deserialize, DeserializeSeed, Deserializer};
+use serde::de::{self, DeserializeSeed, Deserializer};
 
+use super::dyn_decimal::{rescale, BigDecimalVisitor};
 use super::{Context, Error, Presto, PrestoTy};
 
 #[derive(Debug, Default, Eq, PartialEq, Clone)]
@@ -52,10 +53,11 @@ impl<'de, const P: usize, const S: usize> DeserializeSeed<'de> for DecimalSeed<P
     where
         D: Deserializer<'de>,
     {
-        let s = <&'de str as Deserialize<'de>>::deserialize(deserializer)?;
-        let d = bigdecimal::BigDecimal::from_str(s).map_err(de::Error::custom)?;
+        let d = deserializer.deserialize_any(BigDecimalVisitor)?;
+        // integers read leniently may not fit
+        let inner = rescale(&d, P, S).map_err(de::Error::custom)?;
 
-        Ok(Decimal { inner: d })
+        Ok(Decimal { inner })
     }
 }
 
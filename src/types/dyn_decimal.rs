@@ -0,0 +1,206 @@
+use std::fmt;
+use std::str::FromStr;
+
+use bigdecimal::BigDecimal;
+use serde::de::{self, DeserializeSeed, Deserializer, Visitor};
+
+use super::{Context, Error, Presto, PrestoTy, SqlLiteral};
+
+/// max precision of presto's decimal type
+pub const MAX_DECIMAL_PRECISION: usize = 38;
+
+/// A decimal whose precision and scale are only known at runtime, it can be extracted from
+/// any decimal column.
+#[derive(Debug, Eq, PartialEq, Clone)]
+pub struct DynDecimal {
+    inner: BigDecimal,
+    precision: usize,
+    scale: usize,
+}
+
+impl DynDecimal {
+    /// the value is rescaled to `scale`, fails if that loses digits or the value does not fit
+    /// in `precision`.
+    pub fn new(inner: BigDecimal, precision: usize, scale: usize) -> Result<Self, Error> {
+        if precision == 0 || precision > MAX_DECIMAL_PRECISION || scale > precision {
+            return Err(Error::InvalidPrecision(precision));
+        }
+        let inner = rescale(&inner, precision, scale)?;
+        Ok(DynDecimal {
+            inner,
+            precision,
+            scale,
+        })
+    }
+
+    pub fn precision(&self) -> usize {
+        self.precision
+    }
+
+    pub fn scale(&self) -> usize {
+        self.scale
+    }
+
+    pub fn as_bigdecimal(&self) -> &BigDecimal {
+        &self.inner
+    }
+
+    pub fn into_bigdecimal(self) -> BigDecimal {
+        self.inner
+    }
+}
+
+impl fmt::Display for DynDecimal {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.inner)
+    }
+}
+
+impl Presto for DynDecimal {
+    type ValueType<'a> = String;
+    type Seed<'a, 'de> = DynDecimalSeed;
+
+    fn value(&self) -> Self::ValueType<'_> {
+        self.to_string()
+    }
+    fn ty() -> PrestoTy {
+        PrestoTy::DynDecimal
+    }
+    fn seed<'a, 'de>(ctx: &'a Context) -> Self::Seed<'a, 'de> {
+        let (precision, scale) = decimal_params(ctx);
+        DynDecimalSeed { precision, scale }
+    }
+
+    fn empty() -> Self {
+        DynDecimal {
+            inner: BigDecimal::default(),
+            precision: MAX_DECIMAL_PRECISION,
+            scale: 0,
+        }
+    }
+}
+
+impl SqlLiteral for DynDecimal {
+    fn sql_literal(&self) -> String {
+        format!("DECIMAL '{}'", self.inner)
+    }
+}
+
+pub struct DynDecimalSeed {
+    precision: usize,
+    scale: usize,
+}
+
+impl<'de> DeserializeSeed<'de> for DynDecimalSeed {
+    type Value = DynDecimal;
+    fn deserialize<D>(self, deserializer: D) -> Result<Self::Value, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        let d = deserializer.deserialize_any(BigDecimalVisitor)?;
+        DynDecimal::new(d, self.precision, self.scale).map_err(de::Error::custom)
+    }
+}
+
+impl Presto for BigDecimal {
+    type ValueType<'a> = String;
+    type Seed<'a, 'de> = BigDecimalSeed;
+
+    fn value(&self) -> Self::ValueType<'_> {
+        format!("{}", self)
+    }
+    fn ty() -> PrestoTy {
+        PrestoTy::DynDecimal
+    }
+    fn seed<'a, 'de>(_ctx: &'a Context) -> Self::Seed<'a, 'de> {
+        BigDecimalSeed
+    }
+
+    fn empty() -> Self {
+        Default::default()
+    }
+}
+
+impl SqlLiteral for BigDecimal {
+    fn sql_literal(&self) -> String {
+        format!("DECIMAL '{}'", self)
+    }
+}
+
+pub struct BigDecimalSeed;
+
+impl<'de> DeserializeSeed<'de> for BigDecimalSeed {
+    type Value = BigDecimal;
+    fn deserialize<D>(self, deserializer: D) -> Result<Self::Value, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        deserializer.deserialize_any(BigDecimalVisitor)
+    }
+}
+
+// decimals are sent as strings, integers are accepted for lenient coercion
+pub(super) struct BigDecimalVisitor;
+
+impl<'de> Visitor<'de> for BigDecimalVisitor {
+    type Value = BigDecimal;
+
+    fn expecting(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+        formatter.write_str("decimal")
+    }
+
+    fn visit_i64<E: de::Error>(self, v: i64) -> Result<Self::Value, E> {
+        Ok(BigDecimal::from(v))
+    }
+
+    fn visit_u64<E: de::Error>(self, v: u64) -> Result<Self::Value, E> {
+        Ok(BigDecimal::from(v))
+    }
+
+    fn visit_str<E: de::Error>(self, v: &str) -> Result<Self::Value, E> {
+        BigDecimal::from_str(v).map_err(E::custom)
+    }
+}
+
+/// precision and scale of the decimal or integer column the context is built for
+fn decimal_params(ctx: &Context) -> (usize, usize) {
+    use super::PrestoInt::*;
+
+    match ctx.ty() {
+        PrestoTy::Decimal(p, s) => (*p, *s),
+        PrestoTy::PrestoInt(I8) => (3, 0),
+        PrestoTy::PrestoInt(I16) => (5, 0),
+        PrestoTy::PrestoInt(I32) => (10, 0),
+        PrestoTy::PrestoInt(I64) => (19, 0),
+        _ => (MAX_DECIMAL_PRECISION, 0),
+    }
+}
+
+pub(super) fn rescale(d: &BigDecimal, precision: usize, scale: usize) -> Result<BigDecimal, Error> {
+    let scaled = d.with_scale(scale as i64);
+    if &scaled != d || scaled.digits() > precision as u64 {
+        Err(Error::DecimalOutOfRange(format!(
+            "{} does not fit in decimal({},{})",
+            d, precision, scale
+        )))
+    } else {
+        Ok(scaled)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_range() {
+        let d = BigDecimal::from_str("123.4").unwrap();
+        let a = DynDecimal::new(d.clone(), 5, 2).unwrap();
+        assert_eq!(a.value(), "123.40");
+        assert_eq!(a.sql_literal(), "DECIMAL '123.40'");
+
+        assert!(DynDecimal::new(d.clone(), 4, 2).is_err());
+        assert!(DynDecimal::new(d.clone(), 5, 0).is_err());
+        assert!(DynDecimal::new(d, 39, 2).is_err());
+    }
+}
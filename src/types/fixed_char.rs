@@ -1,4 +1,4 @@
-use serde::de::{Deserialize, DeserializeSeed, Deserializer};
+use serde::de::{self, Deserialize, DeserializeSeed, Deserializer};
 
 use super::{Context, Presto, PrestoTy};
 
@@ -40,7 +40,16 @@ impl<'de, const P: usize> DeserializeSeed<'de> for FixedCharSeed<P> {
     where
         D: Deserializer<'de>,
     {
-        let s = String::deserialize(deserializer)?;
+        let mut s = String::deserialize(deserializer)?;
+        // varchar values read leniently are padded like char(P)
+        let len = s.chars().count();
+        if len > P {
+            return Err(de::Error::custom(format!(
+                "{} characters do not fit in char({})",
+                len, P
+            )));
+        }
+        s.extend(std::iter::repeat_n(' ', P - len));
         Ok(FixedChar { inner: s })
     }
 }
@@ -0,0 +1,38 @@
+use serde::de::SeqAccess;
+use serde::ser::SerializeTuple;
+use serde::{Serialize, Serializer};
+
+use super::{Context, Presto};
+
+/// Column level access of structs deriving `Presto`, `#[presto(flatten)]` splices the
+/// columns of a field into its parent with it.
+#[doc(hidden)]
+pub trait RowFields: Presto {
+    /// number of columns, flattened fields are counted by their columns
+    const FIELDS: usize;
+
+    fn serialize_fields<S: SerializeTuple>(&self, s: &mut S) -> Result<(), S::Error>;
+
+    /// decode the column at `idx`, `ctx` is the provided type of the column
+    fn access_field<'a, 'de, A: SeqAccess<'de>>(
+        &mut self,
+        idx: usize,
+        seq: &mut A,
+        ctx: &'a Context<'a>,
+    ) -> Result<(), A::Error>;
+}
+
+/// Value of a struct with flattened fields.
+#[doc(hidden)]
+pub struct FlatValue<'a, T>(pub &'a T);
+
+impl<T: RowFields> Serialize for FlatValue<'_, T> {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        let mut s = serializer.serialize_tuple(T::FIELDS)?;
+        self.0.serialize_fields(&mut s)?;
+        s.end()
+    }
+}
@@ -1,4 +1,6 @@
-use serde::de::{Deserialize, DeserializeSeed, Deserializer};
+use std::fmt;
+
+use serde::de::{self, DeserializeSeed, Deserializer, Visitor};
 
 use super::{Context, Presto, PrestoFloat, PrestoMapKey, PrestoTy};
 
@@ -29,13 +31,51 @@ macro_rules! gen_float {
 
         pub struct $seed;
 
+        impl<'de> Visitor<'de> for $seed {
+            type Value = $ty;
+
+            fn expecting(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+                formatter.write_str(stringify!($ty))
+            }
+
+            fn visit_f64<E: de::Error>(self, v: f64) -> Result<Self::Value, E> {
+                // narrowing a finite double must not overflow
+                let ret = v as $ty;
+                if v.is_finite() && ret.is_infinite() {
+                    Err(E::custom(format!("{} overflows {}", v, stringify!($ty))))
+                } else {
+                    Ok(ret)
+                }
+            }
+
+            fn visit_i64<E: de::Error>(self, v: i64) -> Result<Self::Value, E> {
+                Ok(v as $ty)
+            }
+
+            fn visit_u64<E: de::Error>(self, v: u64) -> Result<Self::Value, E> {
+                Ok(v as $ty)
+            }
+
+            // presto sends `NaN` and `Infinity` as strings, decimals are read leniently
+            fn visit_str<E: de::Error>(self, v: &str) -> Result<Self::Value, E> {
+                let ret = v
+                    .parse::<$ty>()
+                    .map_err(|e| E::custom(format!("parse {} failed, reason: {}", v, e)))?;
+                if ret.is_infinite() && !v.contains("Infinity") {
+                    Err(E::custom(format!("{} overflows {}", v, stringify!($ty))))
+                } else {
+                    Ok(ret)
+                }
+            }
+        }
+
         impl<'de> DeserializeSeed<'de> for $seed {
             type Value = $ty;
             fn deserialize<D>(self, deserializer: D) -> Result<Self::Value, D::Error>
             where
                 D: Deserializer<'de>,
             {
-                Self::Value::deserialize(deserializer)
+                deserializer.deserialize_any(self)
             }
         }
     };
@@ -0,0 +1,96 @@
+use std::fmt;
+
+use geo_types::{Geometry, GeometryCollection};
+use serde::de::{self, DeserializeSeed, Deserializer, Visitor};
+use wkt::{ToWkt, TryFromWkt};
+
+use super::{Context, Presto, PrestoTy, SqlLiteral};
+
+/// `SphericalGeography`, it shares the WKT format with `Geometry` but is a distinct presto type.
+#[derive(Debug, PartialEq, Clone)]
+pub struct SphericalGeography(pub Geometry<f64>);
+
+impl SphericalGeography {
+    pub fn into_inner(self) -> Geometry<f64> {
+        self.0
+    }
+}
+
+macro_rules! gen_geometry {
+    ($ty:ty, $seed:ident, $pty:expr, $to:expr, $from:expr, $literal:expr, $expect:expr) => {
+        impl Presto for $ty {
+            type ValueType<'a> = String;
+            type Seed<'a, 'de> = $seed;
+
+            fn value(&self) -> Self::ValueType<'_> {
+                $to(self).wkt_string()
+            }
+
+            fn ty() -> PrestoTy {
+                $pty
+            }
+
+            fn seed<'a, 'de>(_ctx: &'a Context) -> Self::Seed<'a, 'de> {
+                $seed
+            }
+
+            fn empty() -> Self {
+                $from(Geometry::GeometryCollection(GeometryCollection::default()))
+            }
+        }
+
+        impl SqlLiteral for $ty {
+            fn sql_literal(&self) -> String {
+                format!($literal, self.value())
+            }
+        }
+
+        pub struct $seed;
+
+        impl<'de> Visitor<'de> for $seed {
+            type Value = $ty;
+
+            fn expecting(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+                formatter.write_str($expect)
+            }
+
+            fn visit_str<E>(self, v: &str) -> Result<Self::Value, E>
+            where
+                E: de::Error,
+            {
+                Geometry::try_from_wkt_str(v).map($from).map_err(|e| {
+                    de::Error::custom(format!("deserialize {} failed, reason: {}", $expect, e))
+                })
+            }
+        }
+
+        impl<'de> DeserializeSeed<'de> for $seed {
+            type Value = $ty;
+            fn deserialize<D>(self, deserializer: D) -> Result<Self::Value, D::Error>
+            where
+                D: Deserializer<'de>,
+            {
+                deserializer.deserialize_str(self)
+            }
+        }
+    };
+}
+
+gen_geometry!(
+    Geometry<f64>,
+    GeometrySeed,
+    PrestoTy::Geometry,
+    |g: &Geometry<f64>| g.clone(),
+    |g| g,
+    "ST_GeometryFromText('{}')",
+    "geometry"
+);
+gen_geometry!(
+    SphericalGeography,
+    SphericalGeographySeed,
+    PrestoTy::SphericalGeography,
+    |g: &SphericalGeography| g.0.clone(),
+    SphericalGeography,
+    "to_spherical_geography(ST_GeometryFromText('{}'))",
+    "spherical geography"
+);
@@ -1,4 +1,4 @@
-use serde::de::{Deserialize, DeserializeSeed, Deserializer};
+use serde::de::{self, Deserialize, DeserializeSeed, Deserializer};
 
 use super::{Context, Presto, PrestoInt, PrestoMapKey, PrestoTy};
 
@@ -35,7 +35,10 @@ macro_rules! gen_int {
             where
                 D: Deserializer<'de>,
             {
-                Self::Value::deserialize(deserializer)
+                // any integer column can be read, narrowing fails on overflow
+                let v = i64::deserialize(deserializer)?;
+                <$ty>::try_from(v)
+                    .map_err(|_| de::Error::custom(format!("{} overflows {}", v, stringify!($ty))))
             }
         }
     };
@@ -2,8 +2,10 @@ use std::str::FromStr;
 
 use serde::de::{self, Deserialize, DeserializeSeed, Deserializer};
 
-use super::{Context, Error, Presto, PrestoTy};
-use chrono::{NaiveTime, Timelike};
+use super::{Context, Error, Presto, PrestoTy, SqlLiteral};
+use chrono::{Duration, NaiveTime, Timelike};
+
+const MILLIS_PER_DAY: u64 = 24 * 60 * 60 * 1000;
 
 #[derive(Debug, Default, Eq, PartialEq, Clone)]
 pub struct IntervalDayToSecond {
@@ -16,12 +18,78 @@ pub struct IntervalDayToSecond {
 }
 
 impl IntervalDayToSecond {
+    pub fn from_milliseconds(total: i64) -> Self {
+        let abs = total.unsigned_abs();
+        let millis = abs % MILLIS_PER_DAY;
+        IntervalDayToSecond {
+            negative: total < 0,
+            days: abs / MILLIS_PER_DAY,
+            hours: (millis / 3_600_000) as u8,
+            minutes: (millis / 60_000 % 60) as u8,
+            seconds: (millis / 1000 % 60) as u8,
+            milliseconds: (millis % 1000) as u32,
+        }
+    }
+
     pub fn total_seconds(&self) -> i64 {
         let total = ((self.days * 24 + self.hours as u64) * 60 + self.minutes as u64) * 60
             + self.seconds as u64;
         let sign = if self.negative { -1 } else { 1 };
         total as i64 * sign
     }
+
+    /// fails if the interval does not fit in an `i64` of milliseconds
+    pub fn total_milliseconds(&self) -> Result<i64, Error> {
+        let time = ((self.hours as u64 * 60 + self.minutes as u64) * 60 + self.seconds as u64)
+            * 1000
+            + self.milliseconds as u64;
+        let total = self
+            .days
+            .checked_mul(MILLIS_PER_DAY)
+            .and_then(|d| d.checked_add(time))
+            .and_then(|t| i64::try_from(t).ok())
+            .ok_or_else(|| Error::IntervalOutOfRange(self.value()))?;
+        Ok(if self.negative { -total } else { total })
+    }
+}
+
+/// sub-millisecond parts are truncated
+impl From<Duration> for IntervalDayToSecond {
+    fn from(d: Duration) -> Self {
+        IntervalDayToSecond::from_milliseconds(d.num_milliseconds())
+    }
+}
+
+impl TryFrom<IntervalDayToSecond> for Duration {
+    type Error = Error;
+
+    fn try_from(d: IntervalDayToSecond) -> Result<Self, Self::Error> {
+        let millis = d.total_milliseconds()?;
+        Duration::try_milliseconds(millis).ok_or_else(|| Error::IntervalOutOfRange(d.value()))
+    }
+}
+
+/// sub-millisecond parts are truncated
+impl TryFrom<std::time::Duration> for IntervalDayToSecond {
+    type Error = Error;
+
+    fn try_from(d: std::time::Duration) -> Result<Self, Self::Error> {
+        let millis = i64::try_from(d.as_millis())
+            .map_err(|_| Error::IntervalOutOfRange(format!("{:?}", d)))?;
+        Ok(IntervalDayToSecond::from_milliseconds(millis))
+    }
+}
+
+/// fails for negative intervals
+impl TryFrom<IntervalDayToSecond> for std::time::Duration {
+    type Error = Error;
+
+    fn try_from(d: IntervalDayToSecond) -> Result<Self, Self::Error> {
+        let millis = d.total_milliseconds()?;
+        u64::try_from(millis)
+            .map(std::time::Duration::from_millis)
+            .map_err(|_| Error::IntervalOutOfRange(d.value()))
+    }
 }
 
 impl FromStr for IntervalDayToSecond {
@@ -75,6 +143,16 @@ impl Presto for IntervalDayToSecond {
     }
 }
 
+impl SqlLiteral for IntervalDayToSecond {
+    fn sql_literal(&self) -> String {
+        let v = self.value();
+        match v.strip_prefix('-') {
+            Some(v) => format!("INTERVAL -'{}' DAY TO SECOND", v),
+            None => format!("INTERVAL '{}' DAY TO SECOND", v),
+        }
+    }
+}
+
 pub struct IntervalDayToSecondSeed;
 
 impl<'de> DeserializeSeed<'de> for IntervalDayToSecondSeed {
@@ -89,3 +167,43 @@ impl<'de> DeserializeSeed<'de> for IntervalDayToSecondSeed {
         Ok(d)
     }
 }
+
+gen_proxy!(
+    Duration,
+    DurationSeed,
+    IntervalDayToSecond,
+    IntervalDayToSecondSeed,
+    |d: &Duration| IntervalDayToSecond::from(*d),
+    Duration::try_from,
+    "chrono duration"
+);
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_duration() {
+        let d = IntervalDayToSecond::from_str("-1 02:03:04.005").unwrap();
+        let millis = -((((24 + 2) * 60 + 3) * 60 + 4) * 1000 + 5);
+        assert_eq!(d.total_milliseconds().unwrap(), millis);
+        assert_eq!(
+            Duration::try_from(d.clone()).unwrap(),
+            Duration::milliseconds(millis)
+        );
+        assert_eq!(IntervalDayToSecond::from(Duration::milliseconds(millis)), d);
+        assert_eq!(d.sql_literal(), "INTERVAL -'1 02:03:04.005' DAY TO SECOND");
+        assert!(std::time::Duration::try_from(d).is_err());
+
+        let d = std::time::Duration::from_millis(1500);
+        let i = IntervalDayToSecond::try_from(d).unwrap();
+        assert_eq!(i.value(), "0 00:00:01.500");
+        assert_eq!(std::time::Duration::try_from(i).unwrap(), d);
+
+        let d = Duration::seconds(90);
+        assert_eq!(d.value(), "0 00:01:30.000");
+
+        let i = IntervalDayToSecond::from_str(&format!("{} 00:00:00.000", i64::MAX)).unwrap();
+        assert!(Duration::try_from(i).is_err());
+    }
+}
@@ -2,7 +2,9 @@ use std::str::FromStr;
 
 use serde::de::{self, Deserialize, DeserializeSeed, Deserializer};
 
-use super::{Context, Error, Presto, PrestoTy};
+use chrono::Months;
+
+use super::{Context, Error, Presto, PrestoTy, SqlLiteral};
 
 #[derive(Debug, Default, Eq, PartialEq, Clone)]
 pub struct IntervalYearToMonth {
@@ -12,10 +14,39 @@ pub struct IntervalYearToMonth {
 }
 
 impl IntervalYearToMonth {
+    /// fails if the years do not fit in an `u32`
+    pub fn from_months(total: i64) -> Result<Self, Error> {
+        let abs = total.unsigned_abs();
+        let years = u32::try_from(abs / 12)
+            .map_err(|_| Error::IntervalOutOfRange(format!("{} months", total)))?;
+        Ok(IntervalYearToMonth {
+            negative: total < 0,
+            years,
+            months: (abs % 12) as u32,
+        })
+    }
+
     pub fn total_months(&self) -> i64 {
-        let total = self.years * 12 + self.months;
+        let total = self.years as i64 * 12 + self.months as i64;
         let sign = if self.negative { -1 } else { 1 };
-        total as i64 * sign
+        total * sign
+    }
+}
+
+impl From<Months> for IntervalYearToMonth {
+    fn from(m: Months) -> Self {
+        IntervalYearToMonth::from_months(m.as_u32() as i64).unwrap()
+    }
+}
+
+/// fails for negative intervals or ones longer than `u32::MAX` months
+impl TryFrom<IntervalYearToMonth> for Months {
+    type Error = Error;
+
+    fn try_from(m: IntervalYearToMonth) -> Result<Self, Self::Error> {
+        u32::try_from(m.total_months())
+            .map(Months::new)
+            .map_err(|_| Error::IntervalOutOfRange(m.value()))
     }
 }
 
@@ -67,6 +98,16 @@ impl Presto for IntervalYearToMonth {
     }
 }
 
+impl SqlLiteral for IntervalYearToMonth {
+    fn sql_literal(&self) -> String {
+        let v = self.value();
+        match v.strip_prefix('-') {
+            Some(v) => format!("INTERVAL -'{}' YEAR TO MONTH", v),
+            None => format!("INTERVAL '{}' YEAR TO MONTH", v),
+        }
+    }
+}
+
 pub struct IntervalYearToMonthSeed;
 
 impl<'de> DeserializeSeed<'de> for IntervalYearToMonthSeed {
@@ -81,3 +122,22 @@ impl<'de> DeserializeSeed<'de> for IntervalYearToMonthSeed {
         Ok(d)
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_months() {
+        let m = IntervalYearToMonth::from_str("-1-2").unwrap();
+        assert_eq!(m.total_months(), -14);
+        assert_eq!(m.sql_literal(), "INTERVAL -'1-2' YEAR TO MONTH");
+        assert!(Months::try_from(m).is_err());
+
+        let m = IntervalYearToMonth::from(Months::new(14));
+        assert_eq!(m.value(), "1-2");
+        assert_eq!(Months::try_from(m).unwrap(), Months::new(14));
+
+        assert!(IntervalYearToMonth::from_months(i64::MAX).is_err());
+    }
+}
@@ -0,0 +1,160 @@
+use std::fmt;
+use std::net::{IpAddr, Ipv4Addr, Ipv6Addr};
+use std::str::FromStr;
+
+use serde::de::{self, Deserialize, DeserializeSeed, Deserializer};
+
+use super::{Context, Error, Presto, PrestoMapKey, PrestoTy, SqlLiteral};
+
+/// A CIDR block like `10.0.0.0/8`, the address is truncated to the network address as
+/// presto does.
+#[derive(Debug, Eq, PartialEq, Hash, Clone, Copy)]
+pub struct IpPrefix {
+    addr: IpAddr,
+    prefix_len: u8,
+}
+
+impl IpPrefix {
+    pub fn new(addr: IpAddr, prefix_len: u8) -> Result<Self, Error> {
+        let addr = match addr {
+            IpAddr::V4(a) if prefix_len <= 32 => {
+                let mask = u32::MAX.checked_shl(32 - prefix_len as u32).unwrap_or(0);
+                IpAddr::V4(Ipv4Addr::from(u32::from(a) & mask))
+            }
+            IpAddr::V6(a) if prefix_len <= 128 => {
+                let mask = u128::MAX.checked_shl(128 - prefix_len as u32).unwrap_or(0);
+                IpAddr::V6(Ipv6Addr::from(u128::from(a) & mask))
+            }
+            _ => {
+                return Err(Error::ParseIpPrefixFailed(format!(
+                    "invalid prefix length {} for {}",
+                    prefix_len, addr
+                )))
+            }
+        };
+        Ok(IpPrefix { addr, prefix_len })
+    }
+
+    pub fn addr(&self) -> IpAddr {
+        self.addr
+    }
+
+    pub fn prefix_len(&self) -> u8 {
+        self.prefix_len
+    }
+}
+
+impl Default for IpPrefix {
+    fn default() -> Self {
+        IpPrefix {
+            addr: IpAddr::V4(Ipv4Addr::new(0, 0, 0, 0)),
+            prefix_len: 0,
+        }
+    }
+}
+
+impl FromStr for IpPrefix {
+    type Err = Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let err = || Error::ParseIpPrefixFailed(s.to_string());
+        let (addr, len) = s.split_once('/').ok_or_else(err)?;
+        let addr = IpAddr::from_str(addr).map_err(|_| err())?;
+        let len = u8::from_str(len).map_err(|_| err())?;
+        IpPrefix::new(addr, len)
+    }
+}
+
+impl fmt::Display for IpPrefix {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}/{}", self.addr, self.prefix_len)
+    }
+}
+
+macro_rules! gen_ip_prefix {
+    ($ty:ty, $seed:ident, $empty:expr) => {
+        impl Presto for $ty {
+            type ValueType<'a> = String;
+            type Seed<'a, 'de> = $seed;
+
+            fn value(&self) -> Self::ValueType<'_> {
+                self.to_string()
+            }
+            fn ty() -> PrestoTy {
+                PrestoTy::IpPrefix
+            }
+            fn seed<'a, 'de>(_ctx: &'a Context) -> Self::Seed<'a, 'de> {
+                $seed
+            }
+
+            fn empty() -> Self {
+                $empty
+            }
+        }
+
+        impl PrestoMapKey for $ty {}
+
+        impl SqlLiteral for $ty {
+            fn sql_literal(&self) -> String {
+                format!("IPPREFIX '{}'", self)
+            }
+        }
+
+        pub struct $seed;
+
+        impl<'de> DeserializeSeed<'de> for $seed {
+            type Value = $ty;
+            fn deserialize<D>(self, deserializer: D) -> Result<Self::Value, D::Error>
+            where
+                D: Deserializer<'de>,
+            {
+                let s = <&'de str as Deserialize<'de>>::deserialize(deserializer)?;
+                <$ty>::from_str(s).map_err(de::Error::custom)
+            }
+        }
+    };
+}
+
+gen_ip_prefix!(IpPrefix, IpPrefixSeed, IpPrefix::default());
+
+#[cfg(feature = "ipnet")]
+mod ipnet_impl {
+    use ipnet::IpNet;
+
+    use super::*;
+
+    gen_ip_prefix!(IpNet, IpNetSeed, IpNet::default());
+
+    impl From<IpNet> for IpPrefix {
+        fn from(net: IpNet) -> Self {
+            IpPrefix::new(net.addr(), net.prefix_len()).unwrap()
+        }
+    }
+
+    impl From<IpPrefix> for IpNet {
+        fn from(p: IpPrefix) -> Self {
+            IpNet::new(p.addr, p.prefix_len).unwrap()
+        }
+    }
+}
+
+#[cfg(feature = "ipnet")]
+pub use ipnet_impl::*;
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_prefix() {
+        let p = IpPrefix::from_str("10.1.2.3/8").unwrap();
+        assert_eq!(p.to_string(), "10.0.0.0/8");
+        assert_eq!(p.sql_literal(), "IPPREFIX '10.0.0.0/8'");
+
+        let p = IpPrefix::from_str("2001:db8::ff/32").unwrap();
+        assert_eq!(p.to_string(), "2001:db8::/32");
+
+        assert!(IpPrefix::from_str("10.0.0.0/33").is_err());
+        assert!(IpPrefix::from_str("10.0.0.0").is_err());
+    }
+}
@@ -0,0 +1,200 @@
+use std::str::FromStr;
+
+use chrono::{DateTime, Datelike, FixedOffset, NaiveDate, NaiveDateTime, NaiveTime, Timelike, Utc};
+use chrono_tz::Tz;
+use jiff::civil::{Date, DateTime as CivilDateTime, Time};
+use jiff::tz::{AmbiguousOffset, Offset, TimeZone};
+use jiff::{Timestamp, Zoned};
+
+use super::{
+    DateTimeUtcSeed, NaiveDateSeed, NaiveDateTimeSeed, NaiveTimeSeed, PreciseTimestamp, Zone,
+    ZonedTimestamp, ZonedTimestampSeed,
+};
+
+gen_proxy!(
+    Date,
+    JiffDateSeed,
+    NaiveDate,
+    NaiveDateSeed,
+    date_to_chrono,
+    date_from_chrono,
+    "jiff date"
+);
+gen_proxy!(
+    Time,
+    JiffTimeSeed,
+    NaiveTime,
+    NaiveTimeSeed,
+    time_to_chrono,
+    time_from_chrono,
+    "jiff time"
+);
+gen_proxy!(
+    CivilDateTime,
+    JiffDateTimeSeed,
+    NaiveDateTime,
+    NaiveDateTimeSeed,
+    date_time_to_chrono,
+    date_time_from_chrono,
+    "jiff date time"
+);
+gen_proxy!(
+    Timestamp,
+    JiffTimestampSeed,
+    DateTime<Utc>,
+    DateTimeUtcSeed,
+    timestamp_to_chrono,
+    timestamp_from_chrono,
+    "jiff timestamp"
+);
+gen_proxy!(
+    Zoned,
+    JiffZonedSeed,
+    ZonedTimestamp,
+    ZonedTimestampSeed,
+    zoned_to_presto,
+    zoned_from_presto,
+    "jiff zoned"
+);
+
+// jiff supports a smaller range of dates than chrono, so only the conversions from chrono
+// can fail.
+
+fn date_to_chrono(d: &Date) -> NaiveDate {
+    NaiveDate::from_ymd_opt(d.year() as i32, d.month() as u32, d.day() as u32).unwrap()
+}
+
+fn date_from_chrono(d: NaiveDate) -> Result<Date, jiff::Error> {
+    let year = i16::try_from(d.year())
+        .map_err(|_| jiff::Error::from_args(format_args!("year {} is out of range", d.year())))?;
+    Date::new(year, d.month() as i8, d.day() as i8)
+}
+
+fn time_to_chrono(t: &Time) -> NaiveTime {
+    NaiveTime::from_hms_nano_opt(
+        t.hour() as u32,
+        t.minute() as u32,
+        t.second() as u32,
+        t.subsec_nanosecond() as u32,
+    )
+    .unwrap()
+}
+
+fn time_from_chrono(t: NaiveTime) -> Result<Time, jiff::Error> {
+    Time::new(
+        t.hour() as i8,
+        t.minute() as i8,
+        t.second() as i8,
+        t.nanosecond() as i32,
+    )
+}
+
+fn date_time_to_chrono(d: &CivilDateTime) -> NaiveDateTime {
+    date_to_chrono(&d.date()).and_time(time_to_chrono(&d.time()))
+}
+
+fn date_time_from_chrono(d: NaiveDateTime) -> Result<CivilDateTime, jiff::Error> {
+    Ok(CivilDateTime::from_parts(
+        date_from_chrono(d.date())?,
+        time_from_chrono(d.time())?,
+    ))
+}
+
+fn timestamp_to_chrono(t: &Timestamp) -> DateTime<Utc> {
+    DateTime::from_timestamp(t.as_second(), t.subsec_nanosecond() as u32).unwrap()
+}
+
+fn timestamp_from_chrono(d: DateTime<Utc>) -> Result<Timestamp, jiff::Error> {
+    Timestamp::new(d.timestamp(), d.timestamp_subsec_nanos() as i32)
+}
+
+// zones unknown to chrono-tz fall back to their current offset. jiff allows offsets up to
+// 25:59:59 but chrono's are less than a day, such times are converted to UTC, which keeps the
+// instant.
+fn zoned_to_presto(z: &Zoned) -> ZonedTimestamp {
+    let named = z
+        .time_zone()
+        .iana_name()
+        .and_then(|name| Tz::from_str(name).ok())
+        .map(Zone::Named);
+    let zone = match named {
+        Some(zone) => zone,
+        None => match FixedOffset::east_opt(z.offset().seconds()) {
+            Some(offset) => Zone::Offset(offset),
+            None => return zoned_to_presto(&z.with_time_zone(TimeZone::UTC)),
+        },
+    };
+    let local = PreciseTimestamp::from(date_time_to_chrono(&z.datetime()));
+    ZonedTimestamp::new(local, zone)
+}
+
+// resolved like `ZonedTimestamp::to_date_time`, a local time which occurs twice is the earlier
+// one and a local time which does not exist is rejected
+fn zoned_from_presto(z: ZonedTimestamp) -> Result<Zoned, jiff::Error> {
+    let tz = match z.zone() {
+        Zone::Named(Tz::UTC) => TimeZone::UTC,
+        Zone::Named(tz) => TimeZone::get(tz.name())?,
+        Zone::Offset(offset) => TimeZone::fixed(Offset::from_seconds(offset.local_minus_utc())?),
+    };
+    let zoned = tz.to_ambiguous_zoned(date_time_from_chrono(z.local().to_chrono())?);
+    if let AmbiguousOffset::Gap { .. } = zoned.offset() {
+        return Err(jiff::Error::from_args(format_args!("{} does not exist", z)));
+    }
+    zoned.earlier()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::types::{Presto, SqlLiteral};
+
+    #[test]
+    fn test_round_trip() {
+        let d = CivilDateTime::from_str("2001-08-22T03:04:05.123").unwrap();
+        assert_eq!(d.value(), "2001-08-22 03:04:05.123");
+        assert_eq!(d.sql_literal(), "TIMESTAMP '2001-08-22 03:04:05.123'");
+
+        let t = Time::from_str("01:02:03.123456").unwrap();
+        assert_eq!(t.value(), "01:02:03.123456");
+
+        let z = ZonedTimestamp::from_str("2024-01-01 10:00:00.000 +08:00").unwrap();
+        let zoned = zoned_from_presto(z).unwrap();
+        assert_eq!(zoned.value(), "2024-01-01 10:00:00.000 +08:00");
+
+        let ts =
+            timestamp_from_chrono(zoned_to_presto(&zoned).to_date_time().unwrap().into()).unwrap();
+        assert_eq!(ts.value(), "2024-01-01 02:00:00.000 UTC");
+    }
+
+    #[test]
+    fn test_ambiguous() {
+        // the earlier one when daylight saving time ends
+        let z = ZonedTimestamp::from_str("2024-11-03 01:30:00.000 America/New_York").unwrap();
+        let zoned = zoned_from_presto(z).unwrap();
+        assert_eq!(zoned.offset().seconds(), -4 * 3600);
+        assert_eq!(
+            zoned.timestamp().as_second(),
+            z.to_date_time().unwrap().timestamp()
+        );
+
+        // rejected when daylight saving time starts
+        let z = ZonedTimestamp::from_str("2024-03-10 02:30:00.000 America/New_York").unwrap();
+        assert!(z.to_date_time().is_none());
+        let e = zoned_from_presto(z).unwrap_err();
+        assert!(e.to_string().contains("does not exist"), "{}", e);
+    }
+
+    #[test]
+    fn test_out_of_range() {
+        let d = NaiveDate::from_ymd_opt(10_000, 1, 1).unwrap();
+        assert!(date_from_chrono(d).is_err());
+
+        let d = NaiveDate::from_ymd_opt(100_000, 1, 1).unwrap();
+        let e = date_from_chrono(d).unwrap_err();
+        assert!(e.to_string().contains("100000"));
+
+        let offset = Offset::from_seconds(25 * 3600).unwrap();
+        let z = Timestamp::UNIX_EPOCH.to_zoned(TimeZone::fixed(offset));
+        assert_eq!(z.value(), "1970-01-01 00:00:00.000 UTC");
+    }
+}
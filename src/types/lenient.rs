@@ -0,0 +1,70 @@
+use std::marker::PhantomData;
+use std::ops::{Deref, DerefMut};
+
+use serde::de::{DeserializeSeed, Deserializer};
+
+use super::{Context, Presto, PrestoTy};
+
+/// Opt in to lenient coercions for `T` and everything nested in it: varchar can be read into
+/// `FixedChar`, decimal into floats and integers into decimals.
+#[derive(Debug, Default, Eq, PartialEq, Hash, Clone, Copy)]
+pub struct Lenient<T>(pub T);
+
+impl<T> Lenient<T> {
+    pub fn into_inner(self) -> T {
+        self.0
+    }
+}
+
+impl<T> Deref for Lenient<T> {
+    type Target = T;
+
+    fn deref(&self) -> &T {
+        &self.0
+    }
+}
+
+impl<T> DerefMut for Lenient<T> {
+    fn deref_mut(&mut self) -> &mut T {
+        &mut self.0
+    }
+}
+
+impl<T: Presto> Presto for Lenient<T> {
+    type ValueType<'a> = T::ValueType<'a> where T: 'a;
+    type Seed<'a, 'de> = LenientSeed<'a, 'de, T>;
+
+    fn value(&self) -> Self::ValueType<'_> {
+        self.0.value()
+    }
+
+    fn ty() -> PrestoTy {
+        PrestoTy::Lenient(Box::new(T::ty()))
+    }
+
+    fn seed<'a, 'de>(ctx: &'a Context) -> Self::Seed<'a, 'de> {
+        LenientSeed {
+            inner: T::seed(ctx),
+            _marker: PhantomData,
+        }
+    }
+
+    fn empty() -> Self {
+        Lenient(T::empty())
+    }
+}
+
+pub struct LenientSeed<'a, 'de, T: Presto> {
+    inner: T::Seed<'a, 'de>,
+    _marker: PhantomData<T>,
+}
+
+impl<'a, 'de, T: Presto> DeserializeSeed<'de> for LenientSeed<'a, 'de, T> {
+    type Value = Lenient<T>;
+    fn deserialize<D>(self, deserializer: D) -> Result<Self::Value, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        self.inner.deserialize(deserializer).map(Lenient)
+    }
+}
@@ -1,41 +1,95 @@
+#[cfg(feature = "arrow")]
+mod arrow;
+mod binary;
+mod bing_tile;
 mod boolean;
+mod borrowed;
+mod bounded_string;
 mod data_set;
+#[macro_use]
 mod date_time;
 mod decimal;
+mod dyn_decimal;
 mod fixed_char;
+mod flatten;
 mod float;
+#[cfg(feature = "geo")]
+mod geometry;
 mod integer;
 mod interval_day_to_second;
 mod interval_year_to_month;
 mod ip_address;
+mod ip_prefix;
+#[cfg(feature = "jiff")]
+mod jiff_date_time;
 pub mod json;
+mod lenient;
 mod map;
 mod option;
+#[cfg(feature = "parquet")]
+mod parquet;
+#[cfg(feature = "polars")]
+mod polars;
+mod precise_time;
+mod raw_value;
 mod row;
+#[cfg(feature = "rust_decimal")]
+mod rust_decimal;
 mod seq;
+mod serde_row;
+mod sketch;
 mod string;
+#[cfg(feature = "time")]
+mod time_date_time;
+mod tuple;
 mod util;
 pub mod uuid;
-
+mod value;
+mod with;
+mod zoned;
+
+#[cfg(feature = "parquet")]
+pub use self::parquet::*;
+#[cfg(feature = "rust_decimal")]
+pub use self::rust_decimal::*;
 pub use self::uuid::*;
+pub use binary::*;
+pub use bing_tile::*;
 pub use boolean::*;
+pub use borrowed::*;
+pub use bounded_string::*;
 pub use data_set::*;
 pub use date_time::*;
 pub use decimal::*;
+pub use dyn_decimal::*;
 pub use fixed_char::*;
+pub use flatten::*;
 pub use float::*;
+#[cfg(feature = "geo")]
+pub use geometry::*;
 pub use integer::*;
 pub use interval_day_to_second::*;
 pub use interval_year_to_month::*;
 pub use ip_address::*;
+pub use ip_prefix::*;
+#[cfg(feature = "jiff")]
+pub use jiff_date_time::*;
+pub use lenient::*;
 pub use map::*;
 pub use option::*;
+pub use precise_time::*;
+pub use raw_value::*;
 pub use row::*;
 pub use seq::*;
+pub use serde_row::*;
+pub use sketch::*;
 pub use string::*;
-
-//mod str;
-//pub use self::str::*;
+#[cfg(feature = "time")]
+pub use time_date_time::*;
+pub use tuple::*;
+pub use value::*;
+pub use with::*;
+pub use zoned::*;
 
 use std::borrow::Cow;
 use std::collections::HashMap;
@@ -57,13 +111,30 @@ pub enum Error {
     InvalidPrestoType,
     InvalidColumn,
     InvalidTypeSignature,
+    TypeMismatch(String),
     ParseDecimalFailed(String),
+    InvalidLength(String),
+    InvalidBingTile(String),
+    ParseIpPrefixFailed(String),
+    DecimalOutOfRange(String),
     ParseIntervalMonthFailed,
     ParseIntervalDayFailed,
+    IntervalOutOfRange(String),
+    ParseDateTimeFailed(String),
+    InvalidPrecision(usize),
+    FractionOutOfRange(u64),
     EmptyInPrestoRow,
     NonePrestoRow,
+    ColumnNotFound(String),
+    DecodeValueFailed(String),
+    ConvertToJsonFailed(String),
+    ConvertToArrowFailed(String),
+    ConvertToPolarsFailed(String),
+    ConvertToParquetFailed(String),
 }
 
+impl std::error::Error for Error {}
+
 pub trait Presto {
     type ValueType<'a>: Serialize
     where
@@ -82,20 +153,33 @@ pub trait Presto {
 
 pub trait PrestoMapKey: Presto {}
 
+/// Values which can be rendered back into presto sql.
+pub trait SqlLiteral {
+    fn sql_literal(&self) -> String;
+}
+
 #[derive(Debug)]
 pub struct Context<'a> {
     ty: &'a PrestoTy,
     map: Arc<HashMap<usize, Vec<usize>>>,
+    columns: Arc<HashMap<usize, RowColumns>>,
 }
 
 impl<'a> Context<'a> {
     pub fn new<T: Presto>(provided: &'a PrestoTy) -> Result<Self, Error> {
-        let target = T::ty();
-        let ret = extract(&target, provided)?;
+        Self::from_target(&T::ty(), provided)
+    }
+
+    /// like `new` but takes the target type directly, e.g. `PrestoBorrow::ty()`
+    pub fn from_target(target: &PrestoTy, provided: &'a PrestoTy) -> Result<Self, Error> {
+        let ret = extract(target, provided, false)?;
         let map = HashMap::from_iter(ret);
+        let mut columns = HashMap::new();
+        collect_columns(provided, &mut columns);
         Ok(Context {
             ty: provided,
             map: Arc::new(map),
+            columns: Arc::new(columns),
         })
     }
 
@@ -103,6 +187,7 @@ impl<'a> Context<'a> {
         Context {
             ty,
             map: self.map.clone(),
+            columns: self.columns.clone(),
         }
     }
 
@@ -114,92 +199,303 @@ impl<'a> Context<'a> {
         let key = self.ty as *const PrestoTy as usize;
         self.map.get(&key).map(|r| &**r)
     }
+
+    // names and types of the fields when the provided type is a row or a tuple, shared by all
+    // the values decoded with it
+    pub(crate) fn columns(&self) -> Option<&RowColumns> {
+        let key = self.ty as *const PrestoTy as usize;
+        self.columns.get(&key)
+    }
 }
 
-fn extract(target: &PrestoTy, provided: &PrestoTy) -> Result<Vec<(usize, Vec<usize>)>, Error> {
+fn collect_columns(provided: &PrestoTy, ret: &mut HashMap<usize, RowColumns>) {
+    use PrestoTy::*;
+
+    match provided {
+        Tuple(ts) => {
+            ret.insert(
+                provided as *const PrestoTy as usize,
+                provided.fields().into(),
+            );
+            ts.iter().for_each(|ty| collect_columns(ty, ret));
+        }
+        Row(ts) => {
+            ret.insert(
+                provided as *const PrestoTy as usize,
+                provided.fields().into(),
+            );
+            ts.iter().for_each(|(_, ty)| collect_columns(ty, ret));
+        }
+        Array(ty) | Option(ty) | Lenient(ty) | Defaulted(ty) | QDigest(ty) => {
+            collect_columns(ty, ret)
+        }
+        Map(k, v) => {
+            collect_columns(k, ret);
+            collect_columns(v, ret);
+        }
+        _ => {}
+    }
+}
+
+type RowMaps = Vec<(usize, Vec<usize>)>;
+type RowColumns = Arc<[(String, PrestoTy)]>;
+
+// where and why the target and the provided type do not match
+struct Mismatch {
+    path: Vec<String>,
+    reason: String,
+}
+
+impl Mismatch {
+    fn new(reason: String) -> Self {
+        Mismatch {
+            path: vec![],
+            reason,
+        }
+    }
+
+    fn ty(target: &PrestoTy, provided: &PrestoTy) -> Self {
+        Self::new(format!(
+            "expected {}, found {}",
+            target.full_type(),
+            provided.full_type()
+        ))
+    }
+
+    fn at(mut self, segment: impl Into<String>) -> Self {
+        self.path.insert(0, segment.into());
+        self
+    }
+}
+
+impl From<Mismatch> for Error {
+    fn from(m: Mismatch) -> Self {
+        if m.path.is_empty() {
+            Error::TypeMismatch(m.reason)
+        } else {
+            Error::TypeMismatch(format!("`{}` {}", m.path.join("."), m.reason))
+        }
+    }
+}
+
+// integer and float width never have to match, narrowing is checked when decoding. `lenient`
+// additionally allows varchar to char(n), decimal to float and integer to decimal.
+fn extract(target: &PrestoTy, provided: &PrestoTy, lenient: bool) -> Result<RowMaps, Mismatch> {
     use PrestoTy::*;
 
     match (target, provided) {
         (Unknown, _) => Ok(vec![]),
         (Decimal(p1, s1), Decimal(p2, s2)) if p1 == p2 && s1 == s2 => Ok(vec![]),
-        (Option(ty), provided) => extract(ty, provided),
+        (DynDecimal, Decimal(_, _)) => Ok(vec![]),
+        (Option(ty), provided) => extract(ty, provided, lenient),
+        (Lenient(ty), provided) => extract(ty, provided, true),
+        (Defaulted(ty), provided) => extract(ty, provided, lenient),
         (Boolean, Boolean) => Ok(vec![]),
         (Date, Date) => Ok(vec![]),
-        (Time, Time) => Ok(vec![]),
-        (TimeWithTimeZone, TimeWithTimeZone) => Ok(vec![]),
-        (Timestamp, Timestamp) => Ok(vec![]),
-        (TimestampWithTimeZone, TimestampWithTimeZone) => Ok(vec![]),
+        // the target precision is the most digits it keeps, lenient also accepts wider columns
+        (Time(p1), Time(p2)) if keeps(p1, p2) || lenient => Ok(vec![]),
+        (TimeWithTimeZone(p1), TimeWithTimeZone(p2)) if keeps(p1, p2) || lenient => Ok(vec![]),
+        (Timestamp(p1), Timestamp(p2)) if keeps(p1, p2) || lenient => Ok(vec![]),
+        (TimestampWithTimeZone(p1), TimestampWithTimeZone(p2)) if keeps(p1, p2) || lenient => {
+            Ok(vec![])
+        }
         (IntervalYearToMonth, IntervalYearToMonth) => Ok(vec![]),
         (IntervalDayToSecond, IntervalDayToSecond) => Ok(vec![]),
         (PrestoInt(_), PrestoInt(_)) => Ok(vec![]),
         (PrestoFloat(_), PrestoFloat(_)) => Ok(vec![]),
-        (Varchar, Varchar) => Ok(vec![]),
+        // a bounded target accepts shorter bounds, lenient also accepts any length
+        (Varchar(n1), Varchar(n2)) if fits(n1, n2) || lenient => Ok(vec![]),
+        (VarBinary, VarBinary) => Ok(vec![]),
         (Char(a), Char(b)) if a == b => Ok(vec![]),
+        (Char(_), Varchar(_)) if lenient => Ok(vec![]),
+        (PrestoFloat(_), Decimal(_, _)) if lenient => Ok(vec![]),
+        (Decimal(_, _) | DynDecimal, PrestoInt(_)) if lenient => Ok(vec![]),
         (Tuple(t1), Tuple(t2)) => {
             if t1.len() != t2.len() {
-                Err(Error::InvalidPrestoType)
+                Err(Mismatch::new(format!(
+                    "expected {} fields, found {}",
+                    t1.len(),
+                    t2.len()
+                )))
             } else {
-                t1.lazy_zip(t2).try_flat_map(|(l, r)| extract(l, r))
+                let mut ret = vec![];
+                for (i, (l, r)) in t1.iter().zip(t2).enumerate() {
+                    ret.extend(extract(l, r, lenient).map_err(|e| e.at(i.to_string()))?);
+                }
+                Ok(ret)
             }
         }
-        (Row(t1), Row(t2)) => {
+        // tuples match rows positionally
+        (Tuple(t1), Row(t2)) => {
             if t1.len() != t2.len() {
-                Err(Error::InvalidPrestoType)
+                Err(Mismatch::new(format!(
+                    "expected {} fields, found {}",
+                    t1.len(),
+                    t2.len()
+                )))
             } else {
-                // create a vector of the original element's reference
-                let t1k = t1.sorted_by(|t1, t2| Ord::cmp(&t1.0, &t2.0));
-                let t2k = t2.sorted_by(|t1, t2| Ord::cmp(&t1.0, &t2.0));
-
-                let ret = t1k.lazy_zip(t2k).try_flat_map(|(l, r)| {
-                    if l.0 == r.0 {
-                        extract(&l.1, &r.1)
-                    } else {
-                        Err(Error::InvalidPrestoType)
-                    }
-                })?;
+                let mut ret = vec![];
+                for (l, (name, r)) in t1.iter().zip(t2) {
+                    ret.extend(extract(l, r, lenient).map_err(|e| e.at(name.clone()))?);
+                }
+                Ok(ret)
+            }
+        }
+        // only flattened fields whose type is not a row have no fields
+        (Row(t1), _) if t1.is_empty() => Err(Mismatch::new(
+            "has no fields, only rows can be flattened".into(),
+        )),
+        (Row(t1), Row(t2)) => {
+            // flattened fields may bring in a name the parent already has
+            if let Some((name, _)) = t1
+                .iter()
+                .enumerate()
+                .find(|(i, target)| t1[..*i].iter().any(|t| t.0 == target.0))
+                .map(|(_, target)| target)
+            {
+                return Err(Mismatch::new("is duplicated".into()).at(name.clone()));
+            }
 
-                let map = t2.map(|provided| t1.position(|target| provided.0 == target.0).unwrap());
-                let key = provided as *const PrestoTy as usize;
-                Ok(ret.add_one((key, map)))
+            let mut ret = vec![];
+            for (name, ty) in t1 {
+                match t2.iter().find(|provided| &provided.0 == name) {
+                    Some((_, p)) => {
+                        ret.extend(extract(ty, p, lenient).map_err(|e| e.at(name.clone()))?)
+                    }
+                    None if matches!(ty, Defaulted(_)) => {}
+                    None => return Err(Mismatch::new("is missing".into()).at(name.clone())),
+                }
+            }
+            if let Some((name, _)) = t2
+                .iter()
+                .find(|provided| t1.iter().all(|t| t.0 != provided.0))
+            {
+                return Err(Mismatch::new("is not expected".into()).at(name.clone()));
             }
+
+            let map = t2.map(|provided| t1.position(|target| provided.0 == target.0).unwrap());
+            let key = provided as *const PrestoTy as usize;
+            Ok(ret.add_one((key, map)))
+        }
+        (Array(t1), Array(t2)) => extract(t1, t2, lenient),
+        (Map(t1k, t1v), Map(t2k, t2v)) => {
+            let k = extract(t1k, t2k, lenient).map_err(|e| e.at("key"))?;
+            let v = extract(t1v, t2v, lenient).map_err(|e| e.at("value"))?;
+            Ok(k.chain(v))
         }
-        (Array(t1), Array(t2)) => extract(t1, t2),
-        (Map(t1k, t1v), Map(t2k, t2v)) => Ok(extract(t1k, t2k)?.chain(extract(t1v, t2v)?)),
         (IpAddress, IpAddress) => Ok(vec![]),
+        (IpPrefix, IpPrefix) => Ok(vec![]),
         (Uuid, Uuid) => Ok(vec![]),
         (Json, Json) => Ok(vec![]),
-        _ => Err(Error::InvalidPrestoType),
+        (HyperLogLog, HyperLogLog) => Ok(vec![]),
+        (P4HyperLogLog, P4HyperLogLog) => Ok(vec![]),
+        (QDigest(t1), QDigest(t2)) => extract(t1, t2, lenient),
+        (TDigest, TDigest) => Ok(vec![]),
+        (SetDigest, SetDigest) => Ok(vec![]),
+        (Geometry, Geometry) => Ok(vec![]),
+        (SphericalGeography, SphericalGeography) => Ok(vec![]),
+        (BingTile, BingTile) => Ok(vec![]),
+        (Other(n1, a1), Other(n2, a2)) if n1 == n2 && a1 == a2 => Ok(vec![]),
+        // unknown types are passed through as json
+        (Json, Other(_, _)) => Ok(vec![]),
+        _ => Err(Mismatch::ty(target, provided)),
+    }
+}
+
+// the target type with the precision and scale of the provided columns filled in, dynamic
+// decimals do not know them and time targets only know the most digits they keep. targets
+// which take any type take the provided one. `extract` has already matched the two types.
+fn resolve_params(target: PrestoTy, provided: &PrestoTy) -> PrestoTy {
+    use PrestoTy::*;
+
+    match (target, provided) {
+        (Unknown, provided) => provided.clone(),
+        (DynDecimal, Decimal(p, s)) => Decimal(*p, *s),
+        (Time(_), Time(p)) => Time(*p),
+        (TimeWithTimeZone(_), TimeWithTimeZone(p)) => TimeWithTimeZone(*p),
+        (Timestamp(_), Timestamp(p)) => Timestamp(*p),
+        (TimestampWithTimeZone(_), TimestampWithTimeZone(p)) => TimestampWithTimeZone(*p),
+        (Option(ty), provided) => Option(Box::new(resolve_params(*ty, provided))),
+        (Lenient(ty), provided) => Lenient(Box::new(resolve_params(*ty, provided))),
+        (Defaulted(ty), provided) => Defaulted(Box::new(resolve_params(*ty, provided))),
+        (Tuple(t1), Tuple(t2)) => Tuple(
+            t1.into_iter()
+                .zip(t2)
+                .map(|(l, r)| resolve_params(l, r))
+                .collect(),
+        ),
+        (Tuple(t1), Row(t2)) => Tuple(
+            t1.into_iter()
+                .zip(t2)
+                .map(|(l, (_, r))| resolve_params(l, r))
+                .collect(),
+        ),
+        (Row(t1), Row(t2)) => Row(resolve_row_params(t1, t2)),
+        (Array(t1), Array(t2)) => Array(Box::new(resolve_params(*t1, t2))),
+        (Map(t1k, t1v), Map(t2k, t2v)) => Map(
+            Box::new(resolve_params(*t1k, t2k)),
+            Box::new(resolve_params(*t1v, t2v)),
+        ),
+        (target, _) => target,
     }
 }
 
-// TODO:
-// VarBinary Json
-// TimestampWithTimeZone TimeWithTimeZone
-// HyperLogLog P4HyperLogLog
-// QDigest
+fn resolve_row_params(
+    target: Vec<(String, PrestoTy)>,
+    provided: &[(String, PrestoTy)],
+) -> Vec<(String, PrestoTy)> {
+    target
+        .into_iter()
+        .map(|(name, ty)| match provided.iter().find(|p| p.0 == name) {
+            Some((_, p)) => (name, resolve_params(ty, p)),
+            None => (name, ty),
+        })
+        .collect()
+}
+
 #[derive(Clone, Debug, Eq, PartialEq)]
 pub enum PrestoTy {
     Date,
-    Time,
-    TimeWithTimeZone,
-    Timestamp,
-    TimestampWithTimeZone,
+    // time types carry an optional precision, `None` means the precision is not specified
+    Time(Option<usize>),
+    TimeWithTimeZone(Option<usize>),
+    Timestamp(Option<usize>),
+    TimestampWithTimeZone(Option<usize>),
     Uuid,
     IntervalYearToMonth,
     IntervalDayToSecond,
     Option(Box<PrestoTy>),
+    // enables lenient coercions for the inner type, see `Lenient`
+    Lenient(Box<PrestoTy>),
+    // a row field which takes its default value when the column is missing
+    Defaulted(Box<PrestoTy>),
     Boolean,
     PrestoInt(PrestoInt),
     PrestoFloat(PrestoFloat),
-    Varchar,
+    // `None` means unbounded
+    Varchar(Option<usize>),
+    VarBinary,
     Char(usize),
     Tuple(Vec<PrestoTy>),
     Row(Vec<(String, PrestoTy)>),
     Array(Box<PrestoTy>),
     Map(Box<PrestoTy>, Box<PrestoTy>),
     Decimal(usize, usize),
+    // a decimal whose precision and scale are decided by the provided column
+    DynDecimal,
     IpAddress,
+    IpPrefix,
     Json,
+    HyperLogLog,
+    P4HyperLogLog,
+    QDigest(Box<PrestoTy>),
+    TDigest,
+    SetDigest,
+    Geometry,
+    SphericalGeography,
+    BingTile,
+    // a type this crate does not know, kept with its type arguments
+    Other(String, Vec<ClientTypeSignatureParameter>),
     Unknown,
 }
 
@@ -224,10 +520,12 @@ impl PrestoTy {
 
         let ty = match sig.raw_type {
             RawPrestoTy::Date => PrestoTy::Date,
-            RawPrestoTy::Time => PrestoTy::Time,
-            RawPrestoTy::TimeWithTimeZone => PrestoTy::TimeWithTimeZone,
-            RawPrestoTy::Timestamp => PrestoTy::Timestamp,
-            RawPrestoTy::TimestampWithTimeZone => PrestoTy::TimestampWithTimeZone,
+            RawPrestoTy::Time => PrestoTy::Time(precision(&mut sig)?),
+            RawPrestoTy::TimeWithTimeZone => PrestoTy::TimeWithTimeZone(precision(&mut sig)?),
+            RawPrestoTy::Timestamp => PrestoTy::Timestamp(precision(&mut sig)?),
+            RawPrestoTy::TimestampWithTimeZone => {
+                PrestoTy::TimestampWithTimeZone(precision(&mut sig)?)
+            }
             RawPrestoTy::IntervalYearToMonth => PrestoTy::IntervalYearToMonth,
             RawPrestoTy::IntervalDayToSecond => PrestoTy::IntervalDayToSecond,
             RawPrestoTy::Unknown => PrestoTy::Unknown,
@@ -251,7 +549,8 @@ impl PrestoTy {
             RawPrestoTy::BigInt => PrestoTy::PrestoInt(I64),
             RawPrestoTy::Real => PrestoTy::PrestoFloat(F32),
             RawPrestoTy::Double => PrestoTy::PrestoFloat(F64),
-            RawPrestoTy::VarChar => PrestoTy::Varchar,
+            RawPrestoTy::VarChar => PrestoTy::Varchar(varchar_length(&mut sig)?),
+            RawPrestoTy::VarBinary => PrestoTy::VarBinary,
             RawPrestoTy::Char if sig.arguments.len() == 1 => {
                 if let ClientTypeSignatureParameter::LongLiteral(p) = sig.arguments.pop().unwrap() {
                     PrestoTy::Char(p as usize)
@@ -310,8 +609,26 @@ impl PrestoTy {
                 }
             }
             RawPrestoTy::IpAddress => PrestoTy::IpAddress,
+            RawPrestoTy::IpPrefix => PrestoTy::IpPrefix,
             RawPrestoTy::Uuid => PrestoTy::Uuid,
             RawPrestoTy::Json => PrestoTy::Json,
+            RawPrestoTy::HyperLogLog => PrestoTy::HyperLogLog,
+            RawPrestoTy::P4HyperLogLog => PrestoTy::P4HyperLogLog,
+            RawPrestoTy::QDigest if sig.arguments.len() == 1 => {
+                let sig = sig.arguments.pop().unwrap();
+                if let ClientTypeSignatureParameter::TypeSignature(sig) = sig {
+                    let inner = Self::from_type_signature(sig)?;
+                    PrestoTy::QDigest(Box::new(inner))
+                } else {
+                    return Err(Error::InvalidTypeSignature);
+                }
+            }
+            RawPrestoTy::TDigest => PrestoTy::TDigest,
+            RawPrestoTy::SetDigest => PrestoTy::SetDigest,
+            RawPrestoTy::Geometry => PrestoTy::Geometry,
+            RawPrestoTy::SphericalGeography => PrestoTy::SphericalGeography,
+            RawPrestoTy::BingTile => PrestoTy::BingTile,
+            RawPrestoTy::Other(name) => PrestoTy::Other(name, sig.arguments),
             _ => return Err(Error::InvalidTypeSignature),
         };
 
@@ -344,18 +661,22 @@ impl PrestoTy {
                 ClientTypeSignatureParameter::LongLiteral(p as u64),
                 ClientTypeSignatureParameter::LongLiteral(s as u64),
             ],
+            DynDecimal => vec![],
             Date => vec![],
-            Time => vec![],
-            TimeWithTimeZone => vec![],
-            Timestamp => vec![],
-            TimestampWithTimeZone => vec![],
+            Time(p) | TimeWithTimeZone(p) | Timestamp(p) | TimestampWithTimeZone(p) => p
+                .into_iter()
+                .map(|p| ClientTypeSignatureParameter::LongLiteral(p as u64))
+                .collect(),
             IntervalYearToMonth => vec![],
             IntervalDayToSecond => vec![],
-            Option(t) => return t.into_type_signature(),
+            Option(t) | Lenient(t) | Defaulted(t) => return t.into_type_signature(),
             Boolean => vec![],
             PrestoInt(_) => vec![],
             PrestoFloat(_) => vec![],
-            Varchar => vec![ClientTypeSignatureParameter::LongLiteral(2147483647)],
+            Varchar(n) => vec![ClientTypeSignatureParameter::LongLiteral(
+                n.map(|n| n as u64).unwrap_or(UNBOUNDED_VARCHAR),
+            )],
+            VarBinary => vec![],
             Char(a) => vec![ClientTypeSignatureParameter::LongLiteral(a as u64)],
             Tuple(ts) => ts.map(|ty| {
                 ClientTypeSignatureParameter::NamedTypeSignature(NamedTypeSignature {
@@ -377,31 +698,68 @@ impl PrestoTy {
                 ClientTypeSignatureParameter::TypeSignature(t2.into_type_signature()),
             ],
             IpAddress => vec![],
+            IpPrefix => vec![],
             Uuid => vec![],
             Json => vec![],
+            HyperLogLog => vec![],
+            P4HyperLogLog => vec![],
+            QDigest(t) => vec![ClientTypeSignatureParameter::TypeSignature(
+                t.into_type_signature(),
+            )],
+            TDigest => vec![],
+            SetDigest => vec![],
+            Geometry => vec![],
+            SphericalGeography => vec![],
+            BingTile => vec![],
+            Other(_, args) => args,
         };
 
         TypeSignature::new(raw_ty, params)
     }
 
+    /// type of the `idx`th field of a row or tuple
+    pub fn field_ty(&self, idx: usize) -> Option<&PrestoTy> {
+        match self {
+            PrestoTy::Tuple(ts) => ts.get(idx),
+            PrestoTy::Row(ts) => ts.get(idx).map(|r| &r.1),
+            _ => None,
+        }
+    }
+
+    // names and types of the fields of a row or tuple, tuple fields are named the way presto
+    // names unnamed columns
+    pub(crate) fn fields(&self) -> Vec<(String, PrestoTy)> {
+        match self {
+            PrestoTy::Tuple(ts) => ts
+                .iter()
+                .enumerate()
+                .map(|(i, ty)| (format!("_col{}", i), ty.clone()))
+                .collect(),
+            PrestoTy::Row(ts) => ts.clone(),
+            _ => vec![],
+        }
+    }
+
     pub fn full_type(&self) -> Cow<'static, str> {
         use PrestoTy::*;
 
         match self {
-            Unknown => RawPrestoTy::Unknown.to_str().into(),
+            Unknown => RawPrestoTy::Unknown.to_str(),
             Decimal(p, s) => format!("{}({},{})", RawPrestoTy::Decimal.to_str(), p, s).into(),
-            Option(t) => t.full_type(),
-            Date => RawPrestoTy::Date.to_str().into(),
-            Time => RawPrestoTy::Time.to_str().into(),
-            TimeWithTimeZone => RawPrestoTy::TimeWithTimeZone.to_str().into(),
-            Timestamp => RawPrestoTy::Timestamp.to_str().into(),
-            TimestampWithTimeZone => RawPrestoTy::TimestampWithTimeZone.to_str().into(),
-            IntervalYearToMonth => RawPrestoTy::IntervalYearToMonth.to_str().into(),
-            IntervalDayToSecond => RawPrestoTy::IntervalDayToSecond.to_str().into(),
-            Boolean => RawPrestoTy::Boolean.to_str().into(),
-            PrestoInt(ty) => ty.raw_type().to_str().into(),
-            PrestoFloat(ty) => ty.raw_type().to_str().into(),
-            Varchar => RawPrestoTy::VarChar.to_str().into(),
+            DynDecimal => RawPrestoTy::Decimal.to_str(),
+            Option(t) | Lenient(t) | Defaulted(t) => t.full_type(),
+            Date => RawPrestoTy::Date.to_str(),
+            Time(p) | TimeWithTimeZone(p) | Timestamp(p) | TimestampWithTimeZone(p) => {
+                with_precision(self.raw_type(), p)
+            }
+            IntervalYearToMonth => RawPrestoTy::IntervalYearToMonth.to_str(),
+            IntervalDayToSecond => RawPrestoTy::IntervalDayToSecond.to_str(),
+            Boolean => RawPrestoTy::Boolean.to_str(),
+            PrestoInt(ty) => ty.raw_type().to_str(),
+            PrestoFloat(ty) => ty.raw_type().to_str(),
+            Varchar(None) => RawPrestoTy::VarChar.to_str(),
+            Varchar(Some(n)) => format!("{}({})", RawPrestoTy::VarChar.to_str(), n).into(),
+            VarBinary => RawPrestoTy::VarBinary.to_str(),
             Char(a) => format!("{}({})", RawPrestoTy::Char.to_str(), a).into(),
             Tuple(ts) => format!(
                 "{}({})",
@@ -424,9 +782,22 @@ impl PrestoTy {
                 t2.full_type()
             )
             .into(),
-            IpAddress => RawPrestoTy::IpAddress.to_str().into(),
-            Uuid => RawPrestoTy::Uuid.to_str().into(),
-            Json => RawPrestoTy::Json.to_str().into(),
+            IpAddress => RawPrestoTy::IpAddress.to_str(),
+            IpPrefix => RawPrestoTy::IpPrefix.to_str(),
+            Uuid => RawPrestoTy::Uuid.to_str(),
+            Json => RawPrestoTy::Json.to_str(),
+            HyperLogLog => RawPrestoTy::HyperLogLog.to_str(),
+            P4HyperLogLog => RawPrestoTy::P4HyperLogLog.to_str(),
+            QDigest(t) => format!("{}({})", RawPrestoTy::QDigest.to_str(), t.full_type()).into(),
+            TDigest => RawPrestoTy::TDigest.to_str(),
+            SetDigest => RawPrestoTy::SetDigest.to_str(),
+            Geometry => RawPrestoTy::Geometry.to_str(),
+            SphericalGeography => RawPrestoTy::SphericalGeography.to_str(),
+            BingTile => RawPrestoTy::BingTile.to_str(),
+            Other(name, args) if args.is_empty() => name.clone().into(),
+            Other(name, args) => {
+                format!("{}({})", name, args.lazy_map(argument_type).join(",")).into()
+            }
         }
     }
 
@@ -436,27 +807,107 @@ impl PrestoTy {
         match self {
             Unknown => RawPrestoTy::Unknown,
             Date => RawPrestoTy::Date,
-            Time => RawPrestoTy::Time,
-            TimeWithTimeZone => RawPrestoTy::TimeWithTimeZone,
-            Timestamp => RawPrestoTy::Timestamp,
-            TimestampWithTimeZone => RawPrestoTy::TimestampWithTimeZone,
+            Time(_) => RawPrestoTy::Time,
+            TimeWithTimeZone(_) => RawPrestoTy::TimeWithTimeZone,
+            Timestamp(_) => RawPrestoTy::Timestamp,
+            TimestampWithTimeZone(_) => RawPrestoTy::TimestampWithTimeZone,
             IntervalYearToMonth => RawPrestoTy::IntervalYearToMonth,
             IntervalDayToSecond => RawPrestoTy::IntervalDayToSecond,
-            Decimal(_, _) => RawPrestoTy::Decimal,
-            Option(ty) => ty.raw_type(),
+            Decimal(_, _) | DynDecimal => RawPrestoTy::Decimal,
+            Option(ty) | Lenient(ty) | Defaulted(ty) => ty.raw_type(),
             Boolean => RawPrestoTy::Boolean,
             PrestoInt(ty) => ty.raw_type(),
             PrestoFloat(ty) => ty.raw_type(),
-            Varchar => RawPrestoTy::VarChar,
+            Varchar(_) => RawPrestoTy::VarChar,
+            VarBinary => RawPrestoTy::VarBinary,
             Char(_) => RawPrestoTy::Char,
             Tuple(_) => RawPrestoTy::Row,
             Row(_) => RawPrestoTy::Row,
             Array(_) => RawPrestoTy::Array,
             Map(_, _) => RawPrestoTy::Map,
             IpAddress => RawPrestoTy::IpAddress,
+            IpPrefix => RawPrestoTy::IpPrefix,
             Uuid => RawPrestoTy::Uuid,
             Json => RawPrestoTy::Json,
+            HyperLogLog => RawPrestoTy::HyperLogLog,
+            P4HyperLogLog => RawPrestoTy::P4HyperLogLog,
+            QDigest(_) => RawPrestoTy::QDigest,
+            TDigest => RawPrestoTy::TDigest,
+            SetDigest => RawPrestoTy::SetDigest,
+            Geometry => RawPrestoTy::Geometry,
+            SphericalGeography => RawPrestoTy::SphericalGeography,
+            BingTile => RawPrestoTy::BingTile,
+            Other(name, _) => RawPrestoTy::Other(name.clone()),
+        }
+    }
+}
+
+fn precision(sig: &mut TypeSignature) -> Result<Option<usize>, Error> {
+    match sig.arguments.pop() {
+        None => Ok(None),
+        Some(ClientTypeSignatureParameter::LongLiteral(p)) if sig.arguments.is_empty() => {
+            Ok(Some(p as usize))
         }
+        _ => Err(Error::InvalidTypeSignature),
+    }
+}
+
+// render a type argument of an unknown type
+fn argument_type(arg: &ClientTypeSignatureParameter) -> String {
+    let full_type = |sig: &TypeSignature| match PrestoTy::from_type_signature(sig.clone()) {
+        Ok(ty) => ty.full_type().into_owned(),
+        Err(_) => sig.raw_type.to_str().into_owned(),
+    };
+    match arg {
+        ClientTypeSignatureParameter::TypeSignature(sig) => full_type(sig),
+        ClientTypeSignatureParameter::NamedTypeSignature(sig) => match &sig.field_name {
+            Some(name) => format!("{} {}", name.name, full_type(&sig.type_signature)),
+            None => full_type(&sig.type_signature),
+        },
+        ClientTypeSignatureParameter::LongLiteral(n) => n.to_string(),
+    }
+}
+
+// presto's length of an unbounded varchar
+const UNBOUNDED_VARCHAR: u64 = 2147483647;
+
+fn varchar_length(sig: &mut TypeSignature) -> Result<Option<usize>, Error> {
+    match precision(sig)? {
+        Some(n) if n as u64 == UNBOUNDED_VARCHAR => Ok(None),
+        n => Ok(n),
+    }
+}
+
+// whether strings of length `provided` always fit in `target`
+fn fits(target: &Option<usize>, provided: &Option<usize>) -> bool {
+    match (target, provided) {
+        (None, _) => true,
+        (Some(t), Some(p)) => p <= t,
+        (Some(_), None) => false,
+    }
+}
+
+// presto's precision of time and timestamp columns which do not carry one
+const DEFAULT_PRECISION: usize = 3;
+
+// whether a target keeping `target` fractional digits can hold a column of the provided
+// precision, `None` keeps any precision and columns without one have the default of 3 digits
+fn keeps(target: &Option<usize>, provided: &Option<usize>) -> bool {
+    match target {
+        None => true,
+        Some(t) => provided.unwrap_or(DEFAULT_PRECISION) <= *t,
+    }
+}
+
+// `timestamp(3) with time zone`, the precision follows the first word
+fn with_precision(raw_ty: RawPrestoTy, precision: &Option<usize>) -> Cow<'static, str> {
+    let s = raw_ty.to_str();
+    match precision {
+        None => s,
+        Some(p) => match s.split_once(' ') {
+            Some((head, tail)) => format!("{}({}) {}", head, p, tail).into(),
+            None => format!("{}({})", s, p).into(),
+        },
     }
 }
 
@@ -44,7 +44,7 @@ impl<'a, T> OptionSeed<'a, T> {
 impl<'a, 'de, T: Presto> Visitor<'de> for OptionSeed<'a, T> {
     type Value = Option<T>;
     fn expecting(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
-        formatter.write_str(T::ty().raw_type().to_str())
+        formatter.write_str(&T::ty().raw_type().to_str())
     }
 
     fn visit_none<E>(self) -> Result<Self::Value, E>
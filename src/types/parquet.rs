@@ -0,0 +1,231 @@
+use std::io::{self, Write};
+use std::sync::Arc;
+
+use arrow_array::cast::AsArray;
+use arrow_array::types::{
+    DurationMillisecondType, Int32Type, Int64Type, IntervalYearMonthType, Time32MillisecondType,
+    Time32SecondType, TimestampMillisecondType, TimestampSecondType,
+};
+use arrow_array::{Array, ArrayRef, ListArray, MapArray, RecordBatch, StructArray};
+use arrow_schema::{DataType, FieldRef, IntervalUnit, Schema, SchemaRef, TimeUnit};
+use parquet::arrow::{ArrowSchemaConverter, ArrowWriter};
+use parquet::basic::Compression;
+use parquet::file::properties::WriterProperties;
+use parquet::schema::types::SchemaDescriptor;
+
+use super::{DataSet, Error, Row};
+use crate::format::RowWriter;
+
+// parquet has no durations and no second units, and its intervals can not be read by most
+// engines, so intervals are kept as numbers of months or milliseconds
+fn parquet_type(ty: &DataType) -> DataType {
+    match ty {
+        DataType::Time32(TimeUnit::Second) => DataType::Time32(TimeUnit::Millisecond),
+        DataType::Timestamp(TimeUnit::Second, tz) => {
+            DataType::Timestamp(TimeUnit::Millisecond, tz.clone())
+        }
+        DataType::Interval(IntervalUnit::YearMonth) => DataType::Int32,
+        DataType::Duration(TimeUnit::Millisecond) => DataType::Int64,
+        DataType::List(field) => DataType::List(parquet_field(field)),
+        DataType::Map(field, sorted) => DataType::Map(parquet_field(field), *sorted),
+        DataType::Struct(fields) => DataType::Struct(fields.iter().map(parquet_field).collect()),
+        ty => ty.clone(),
+    }
+}
+
+fn parquet_field(field: &FieldRef) -> FieldRef {
+    let ty = parquet_type(field.data_type());
+    Arc::new(field.as_ref().clone().with_data_type(ty))
+}
+
+impl DataSet<Row> {
+    /// Decimals keep their precision, timestamps keep their unit, arrays, maps and rows become
+    /// lists, maps and structs, intervals are numbers of months or milliseconds.
+    pub fn parquet_schema(&self) -> Result<SchemaDescriptor, Error> {
+        ArrowSchemaConverter::new()
+            .convert(&self.parquet_arrow_schema())
+            .map_err(|e| Error::ConvertToParquetFailed(e.to_string()))
+    }
+
+    fn parquet_arrow_schema(&self) -> Schema {
+        let schema = self.arrow_schema();
+        Schema::new(
+            schema
+                .fields()
+                .iter()
+                .map(parquet_field)
+                .collect::<Vec<_>>(),
+        )
+    }
+
+    fn parquet_batch(&self, schema: SchemaRef) -> Result<RecordBatch, Error> {
+        let batch = self.to_record_batch()?;
+        let columns = batch
+            .columns()
+            .iter()
+            .zip(schema.fields())
+            .map(|(column, field)| to_parquet(column, field.data_type()))
+            .collect::<Result<Vec<_>, _>>()?;
+        RecordBatch::try_new(schema, columns).map_err(parquet_error)
+    }
+}
+
+/// Pages are buffered into row groups of `WriterProperties::max_row_group_size` rows, the
+/// schema is built from the columns of the first page. Files are compressed with snappy unless
+/// other properties are given.
+pub struct ParquetWriter<W: Write + Send> {
+    inner: Option<W>,
+    props: WriterProperties,
+    schema: Option<SchemaRef>,
+    writer: Option<ArrowWriter<W>>,
+}
+
+impl<W: Write + Send> ParquetWriter<W> {
+    pub fn new(inner: W) -> Self {
+        let props = WriterProperties::builder()
+            .set_compression(Compression::SNAPPY)
+            .build();
+        ParquetWriter {
+            inner: Some(inner),
+            props,
+            schema: None,
+            writer: None,
+        }
+    }
+
+    pub fn properties(mut self, props: WriterProperties) -> Self {
+        self.props = props;
+        self
+    }
+
+    /// the footer is written if `finish` is not called yet
+    pub fn into_inner(mut self) -> io::Result<W> {
+        match self.writer.take() {
+            Some(writer) => Ok(writer.into_inner()?),
+            None => Ok(self.inner.take().expect("inner writer")),
+        }
+    }
+}
+
+impl<W: Write + Send> RowWriter for ParquetWriter<W> {
+    fn write(&mut self, data: &DataSet<Row>) -> io::Result<()> {
+        let schema = match &self.schema {
+            Some(schema) => schema.clone(),
+            None => {
+                let schema = Arc::new(data.parquet_arrow_schema());
+                let inner = self.inner.take().expect("inner writer");
+                let writer = ArrowWriter::try_new(inner, schema.clone(), Some(self.props.clone()))?;
+                self.writer = Some(writer);
+                self.schema = Some(schema.clone());
+                schema
+            }
+        };
+        let writer = match &mut self.writer {
+            Some(writer) => writer,
+            None => return Err(io::Error::other("parquet writer is finished")),
+        };
+        if !data.is_empty() {
+            let batch = data.parquet_batch(schema).map_err(io::Error::other)?;
+            writer.write(&batch)?;
+        }
+        Ok(())
+    }
+
+    /// a parquet file needs a schema, so there must be a page with columns
+    fn finish(&mut self) -> io::Result<()> {
+        if self.schema.is_none() {
+            return Err(io::Error::new(
+                io::ErrorKind::InvalidData,
+                "no columns to write",
+            ));
+        }
+        if let Some(writer) = self.writer.take() {
+            let mut inner = writer.into_inner()?;
+            inner.flush()?;
+            self.inner = Some(inner);
+        }
+        Ok(())
+    }
+}
+
+fn parquet_error(e: arrow_schema::ArrowError) -> Error {
+    Error::ConvertToParquetFailed(e.to_string())
+}
+
+// `ty` is the `parquet_type` of the array type
+fn to_parquet(array: &ArrayRef, ty: &DataType) -> Result<ArrayRef, Error> {
+    if array.data_type() == ty {
+        return Ok(array.clone());
+    }
+
+    let ret: ArrayRef = match (array.data_type(), ty) {
+        (DataType::Time32(TimeUnit::Second), _) => Arc::new(
+            array
+                .as_primitive::<Time32SecondType>()
+                .unary::<_, Time32MillisecondType>(|v| v * 1_000),
+        ),
+        (DataType::Timestamp(TimeUnit::Second, tz), _) => Arc::new(
+            array
+                .as_primitive::<TimestampSecondType>()
+                .unary::<_, TimestampMillisecondType>(|v| v * 1_000)
+                .with_timezone_opt(tz.clone()),
+        ),
+        (DataType::Interval(IntervalUnit::YearMonth), _) => Arc::new(
+            array
+                .as_primitive::<IntervalYearMonthType>()
+                .reinterpret_cast::<Int32Type>(),
+        ),
+        (DataType::Duration(TimeUnit::Millisecond), _) => Arc::new(
+            array
+                .as_primitive::<DurationMillisecondType>()
+                .reinterpret_cast::<Int64Type>(),
+        ),
+        (DataType::List(_), DataType::List(field)) => {
+            let array = array.as_list::<i32>();
+            Arc::new(
+                ListArray::try_new(
+                    field.clone(),
+                    array.offsets().clone(),
+                    to_parquet(array.values(), field.data_type())?,
+                    array.nulls().cloned(),
+                )
+                .map_err(parquet_error)?,
+            )
+        }
+        (DataType::Map(_, _), DataType::Map(field, sorted)) => {
+            let array = array.as_map();
+            let entries: ArrayRef = Arc::new(array.entries().clone());
+            let entries = to_parquet(&entries, field.data_type())?;
+            Arc::new(
+                MapArray::try_new(
+                    field.clone(),
+                    array.offsets().clone(),
+                    entries.as_struct().clone(),
+                    array.nulls().cloned(),
+                    *sorted,
+                )
+                .map_err(parquet_error)?,
+            )
+        }
+        (DataType::Struct(_), DataType::Struct(fields)) => {
+            let array = array.as_struct();
+            let columns = array
+                .columns()
+                .iter()
+                .zip(fields)
+                .map(|(column, field)| to_parquet(column, field.data_type()))
+                .collect::<Result<Vec<_>, _>>()?;
+            Arc::new(
+                StructArray::try_new(fields.clone(), columns, array.nulls().cloned())
+                    .map_err(parquet_error)?,
+            )
+        }
+        (from, to) => {
+            return Err(Error::ConvertToParquetFailed(format!(
+                "can not convert {} to {}",
+                from, to
+            )))
+        }
+    };
+    Ok(ret)
+}
@@ -0,0 +1,230 @@
+use std::sync::Arc;
+
+use bigdecimal::ToPrimitive;
+use chrono::{NaiveDate, NaiveDateTime, NaiveTime, Timelike};
+use polars::prelude::{
+    AnyValue, DataFrame, DataType, Field, IntoColumn, Series, TimeUnit, TimeZone,
+};
+
+use super::{DataSet, Error, PrestoTy, PrestoValue, Row};
+
+impl PrestoTy {
+    /// Maps become lists of `key`, `value` structs, `interval year to month` is the number of
+    /// months and `time with time zone` is normalized to UTC. Types polars does not have are
+    /// kept as their presto text form.
+    pub fn polars_type(&self) -> DataType {
+        use super::PrestoFloat::*;
+        use super::PrestoInt::*;
+        use PrestoTy::*;
+
+        match self {
+            Unknown => DataType::Null,
+            Boolean => DataType::Boolean,
+            PrestoInt(I8) => DataType::Int8,
+            PrestoInt(I16) => DataType::Int16,
+            PrestoInt(I32) => DataType::Int32,
+            PrestoInt(I64) => DataType::Int64,
+            PrestoFloat(F32) => DataType::Float32,
+            PrestoFloat(F64) => DataType::Float64,
+            Decimal(p, s) => DataType::Decimal(Some(*p), Some(*s)),
+            Date => DataType::Date,
+            Time(_) | TimeWithTimeZone(_) => DataType::Time,
+            Timestamp(p) => DataType::Datetime(time_unit(p), None),
+            TimestampWithTimeZone(p) => DataType::Datetime(time_unit(p), Some(TimeZone::UTC)),
+            IntervalYearToMonth => DataType::Int32,
+            IntervalDayToSecond => DataType::Duration(TimeUnit::Milliseconds),
+            VarBinary | HyperLogLog | P4HyperLogLog | QDigest(_) | TDigest | SetDigest => {
+                DataType::Binary
+            }
+            Varchar(_)
+            | Char(_)
+            | Uuid
+            | IpAddress
+            | IpPrefix
+            | Json
+            | Geometry
+            | SphericalGeography
+            | DynDecimal
+            | Other(_, _) => DataType::String,
+            BingTile => DataType::Struct(bing_tile_fields()),
+            Array(ty) => DataType::List(Box::new(ty.polars_type())),
+            Map(k, v) => DataType::List(Box::new(DataType::Struct(entry_fields(k, v)))),
+            Tuple(_) | Row(_) => DataType::Struct(row_fields(&self.fields())),
+            Option(ty) | Lenient(ty) | Defaulted(ty) => ty.polars_type(),
+        }
+    }
+}
+
+// polars has no seconds, timestamps without precision keep milliseconds
+fn time_unit(precision: &Option<usize>) -> TimeUnit {
+    match precision.unwrap_or(3) {
+        0..=3 => TimeUnit::Milliseconds,
+        4..=6 => TimeUnit::Microseconds,
+        _ => TimeUnit::Nanoseconds,
+    }
+}
+
+fn row_fields(columns: &[(String, PrestoTy)]) -> Vec<Field> {
+    columns
+        .iter()
+        .map(|(name, ty)| Field::new(name.into(), ty.polars_type()))
+        .collect()
+}
+
+fn entry_fields(k: &PrestoTy, v: &PrestoTy) -> Vec<Field> {
+    vec![
+        Field::new("key".into(), k.polars_type()),
+        Field::new("value".into(), v.polars_type()),
+    ]
+}
+
+fn bing_tile_fields() -> Vec<Field> {
+    vec![
+        Field::new("x".into(), DataType::UInt32),
+        Field::new("y".into(), DataType::UInt32),
+        Field::new("zoom".into(), DataType::UInt8),
+    ]
+}
+
+impl DataSet<Row> {
+    pub fn to_dataframe(&self) -> Result<DataFrame, Error> {
+        let rows = self.as_slice();
+        let mut columns = Vec::with_capacity(self.columns().len());
+        for (idx, (name, ty)) in self.columns().iter().enumerate() {
+            let values = rows
+                .iter()
+                .map(|row| row.values().get(idx).unwrap_or(&PrestoValue::Null))
+                .collect::<Vec<_>>();
+            columns.push(to_series(name, ty, &values)?.into_column());
+        }
+        DataFrame::new(columns).map_err(polars_error)
+    }
+}
+
+fn polars_error(e: polars::error::PolarsError) -> Error {
+    Error::ConvertToPolarsFailed(e.to_string())
+}
+
+fn mismatch(ty: &PrestoTy, v: &PrestoValue) -> Error {
+    Error::ConvertToPolarsFailed(format!("can not convert {:?} to {}", v, ty.full_type()))
+}
+
+fn to_series(name: &str, ty: &PrestoTy, values: &[&PrestoValue]) -> Result<Series, Error> {
+    let values = values
+        .iter()
+        .map(|v| to_any_value(ty, v))
+        .collect::<Result<Vec<_>, _>>()?;
+    Series::from_any_values_and_dtype(name.into(), &values, &ty.polars_type(), true)
+        .map_err(polars_error)
+}
+
+fn to_any_value(ty: &PrestoTy, v: &PrestoValue) -> Result<AnyValue<'static>, Error> {
+    use PrestoValue as V;
+
+    let ret = match (ty, v) {
+        (_, V::Null) => AnyValue::Null,
+        (PrestoTy::Option(ty) | PrestoTy::Lenient(ty) | PrestoTy::Defaulted(ty), v) => {
+            to_any_value(ty, v)?
+        }
+        (_, V::Boolean(v)) => AnyValue::Boolean(*v),
+        (_, V::TinyInt(v)) => AnyValue::Int8(*v),
+        (_, V::SmallInt(v)) => AnyValue::Int16(*v),
+        (_, V::Integer(v)) => AnyValue::Int32(*v),
+        (_, V::BigInt(v)) => AnyValue::Int64(*v),
+        (_, V::Real(v)) => AnyValue::Float32(*v),
+        (_, V::Double(v)) => AnyValue::Float64(*v),
+        (PrestoTy::Decimal(_, s), V::Decimal(d)) => {
+            let (n, _) = d
+                .as_bigdecimal()
+                .with_scale(*s as i64)
+                .into_bigint_and_exponent();
+            let n = n.to_i128().ok_or_else(|| mismatch(ty, v))?;
+            AnyValue::Decimal(n, *s)
+        }
+        (_, V::Date(v)) => {
+            let epoch = NaiveDate::from_ymd_opt(1970, 1, 1).unwrap();
+            let days = v.signed_duration_since(epoch).num_days();
+            AnyValue::Date(i32::try_from(days).map_err(|_| mismatch(ty, &V::Date(*v)))?)
+        }
+        (_, V::Time(t)) => AnyValue::Time(time_value(t.to_chrono())),
+        (_, V::TimeWithTimeZone(t)) => AnyValue::Time(time_value(t.to_utc())),
+        (PrestoTy::Timestamp(p), V::Timestamp(t)) => {
+            let unit = time_unit(p);
+            let t = timestamp_value(t.to_chrono(), &unit).ok_or_else(|| mismatch(ty, v))?;
+            AnyValue::DatetimeOwned(t, unit, None)
+        }
+        (PrestoTy::TimestampWithTimeZone(p), V::TimestampWithTimeZone(t)) => {
+            let unit = time_unit(p);
+            let t = t
+                .to_date_time()
+                .and_then(|t| timestamp_value(t.naive_utc(), &unit))
+                .ok_or_else(|| mismatch(ty, v))?;
+            AnyValue::DatetimeOwned(t, unit, Some(Arc::new(TimeZone::UTC)))
+        }
+        (_, V::IntervalYearToMonth(i)) => {
+            AnyValue::Int32(i32::try_from(i.total_months()).map_err(|_| mismatch(ty, v))?)
+        }
+        (_, V::IntervalDayToSecond(i)) => {
+            let ms = i.total_milliseconds().map_err(|_| mismatch(ty, v))?;
+            AnyValue::Duration(ms, TimeUnit::Milliseconds)
+        }
+        (_, V::VarBinary(b)) => AnyValue::BinaryOwned(b.to_vec()),
+        (_, V::HyperLogLog(b)) => AnyValue::BinaryOwned(b.as_bytes().to_vec()),
+        (_, V::P4HyperLogLog(b)) => AnyValue::BinaryOwned(b.as_bytes().to_vec()),
+        (_, V::QDigest(b)) => AnyValue::BinaryOwned(b.as_bytes().to_vec()),
+        (_, V::TDigest(b)) => AnyValue::BinaryOwned(b.as_bytes().to_vec()),
+        (_, V::SetDigest(b)) => AnyValue::BinaryOwned(b.as_bytes().to_vec()),
+        (_, V::BingTile(t)) => {
+            let values = vec![
+                AnyValue::UInt32(t.x()),
+                AnyValue::UInt32(t.y()),
+                AnyValue::UInt8(t.zoom()),
+            ];
+            AnyValue::StructOwned(Box::new((values, bing_tile_fields())))
+        }
+        (PrestoTy::Array(inner), V::Array(items)) => {
+            let items = items.iter().collect::<Vec<_>>();
+            AnyValue::List(to_series("", inner, &items)?)
+        }
+        (PrestoTy::Map(k, v), V::Map(entries)) => {
+            let fields = entry_fields(k, v);
+            let entries = entries
+                .iter()
+                .map(|(key, value)| {
+                    let values = vec![to_any_value(k, key)?, to_any_value(v, value)?];
+                    Ok(AnyValue::StructOwned(Box::new((values, fields.clone()))))
+                })
+                .collect::<Result<Vec<_>, Error>>()?;
+            let dtype = DataType::Struct(fields);
+            let series = Series::from_any_values_and_dtype("".into(), &entries, &dtype, true)
+                .map_err(polars_error)?;
+            AnyValue::List(series)
+        }
+        (PrestoTy::Tuple(_) | PrestoTy::Row(_), V::Row(row)) => {
+            let columns = ty.fields();
+            let values = columns
+                .iter()
+                .zip(row.values())
+                .map(|((_, ty), v)| to_any_value(ty, v))
+                .collect::<Result<Vec<_>, _>>()?;
+            AnyValue::StructOwned(Box::new((values, row_fields(&columns))))
+        }
+        (_, V::Array(_) | V::Map(_) | V::Row(_) | V::Decimal(_)) => return Err(mismatch(ty, v)),
+        (_, V::Timestamp(_) | V::TimestampWithTimeZone(_)) => return Err(mismatch(ty, v)),
+        (_, v) => AnyValue::StringOwned(v.to_text().into()),
+    };
+    Ok(ret)
+}
+
+fn time_value(v: NaiveTime) -> i64 {
+    v.num_seconds_from_midnight() as i64 * 1_000_000_000 + v.nanosecond() as i64
+}
+
+fn timestamp_value(v: NaiveDateTime, unit: &TimeUnit) -> Option<i64> {
+    let v = v.and_utc();
+    match unit {
+        TimeUnit::Milliseconds => Some(v.timestamp_millis()),
+        TimeUnit::Microseconds => Some(v.timestamp_micros()),
+        TimeUnit::Nanoseconds => v.timestamp_nanos_opt(),
+    }
+}
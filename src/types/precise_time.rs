@@ -0,0 +1,257 @@
+use std::fmt;
+use std::str::FromStr;
+
+use chrono::naive::{NaiveDate, NaiveDateTime, NaiveTime};
+use chrono::Timelike;
+use serde::de::{self, DeserializeSeed, Deserializer, Visitor};
+
+use super::{Context, Error, Presto, PrestoTy, SqlLiteral};
+
+pub(super) const MAX_PRECISION: usize = 12;
+
+const PICOS_PER_SECOND: u64 = 1_000_000_000_000;
+
+// `timestamp(p)` and `time(p)` keep up to picoseconds, which is beyond chrono's resolution.
+// These types keep the whole seconds as a chrono value, the fraction as picoseconds and the
+// precision they were read with, so that values are written back exactly as they were read.
+macro_rules! gen_precise {
+    ($ty:ident, $inner:ty, $seed:ident, $pty:expr, $format:expr, $literal:expr, $empty:expr, $expect:expr) => {
+        #[derive(Debug, Eq, PartialEq, Ord, PartialOrd, Hash, Clone, Copy)]
+        pub struct $ty {
+            inner: $inner,
+            picos: u64,
+            precision: usize,
+        }
+
+        impl $ty {
+            /// the fraction is truncated to `precision` digits.
+            pub fn new(inner: $inner, picos: u64, precision: usize) -> Result<Self, Error> {
+                if precision > MAX_PRECISION {
+                    return Err(Error::InvalidPrecision(precision));
+                }
+                if picos >= PICOS_PER_SECOND {
+                    return Err(Error::FractionOutOfRange(picos));
+                }
+                let unit = 10u64.pow((MAX_PRECISION - precision) as u32);
+                Ok($ty {
+                    inner: inner.with_nanosecond(0).unwrap(),
+                    picos: picos / unit * unit,
+                    precision,
+                })
+            }
+
+            pub fn picoseconds(&self) -> u64 {
+                self.picos
+            }
+
+            pub fn precision(&self) -> usize {
+                self.precision
+            }
+
+            /// truncate to nanoseconds
+            pub fn to_chrono(&self) -> $inner {
+                self.inner
+                    .with_nanosecond((self.picos / 1_000) as u32)
+                    .unwrap()
+            }
+        }
+
+        // use the shortest lossless precision of 3, 6 and 9, same as chrono types
+        impl From<$inner> for $ty {
+            fn from(v: $inner) -> Self {
+                let nanos = v.nanosecond();
+                let precision = if nanos % 1_000_000 == 0 {
+                    3
+                } else if nanos % 1_000 == 0 {
+                    6
+                } else {
+                    9
+                };
+                $ty {
+                    inner: v.with_nanosecond(0).unwrap(),
+                    picos: nanos as u64 * 1_000,
+                    precision,
+                }
+            }
+        }
+
+        impl FromStr for $ty {
+            type Err = Error;
+
+            fn from_str(s: &str) -> Result<Self, Self::Err> {
+                let (head, fraction) = match s.split_once('.') {
+                    Some((head, fraction)) => (head, fraction),
+                    None => (s, ""),
+                };
+                let inner = <$inner>::parse_from_str(head, $format)
+                    .map_err(|e| Error::ParseDateTimeFailed(format!("{}", e)))?;
+                let (picos, precision) = parse_fraction(fraction)?;
+                Ok($ty {
+                    inner,
+                    picos,
+                    precision,
+                })
+            }
+        }
+
+        impl fmt::Display for $ty {
+            fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+                write!(
+                    f,
+                    "{}{}",
+                    self.inner.format($format),
+                    format_fraction(self.picos, self.precision)
+                )
+            }
+        }
+
+        impl Presto for $ty {
+            type ValueType<'a> = String;
+            type Seed<'a, 'de> = $seed;
+
+            fn value(&self) -> Self::ValueType<'_> {
+                self.to_string()
+            }
+
+            fn ty() -> PrestoTy {
+                $pty
+            }
+
+            fn seed<'a, 'de>(_ctx: &'a Context) -> Self::Seed<'a, 'de> {
+                $seed
+            }
+
+            fn empty() -> Self {
+                $ty::from($empty)
+            }
+        }
+
+        impl SqlLiteral for $ty {
+            fn sql_literal(&self) -> String {
+                format!("{} '{}'", $literal, self)
+            }
+        }
+
+        pub struct $seed;
+
+        impl<'de> Visitor<'de> for $seed {
+            type Value = $ty;
+
+            fn expecting(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+                formatter.write_str($expect)
+            }
+
+            fn visit_str<E>(self, v: &str) -> Result<Self::Value, E>
+            where
+                E: de::Error,
+            {
+                $ty::from_str(v).map_err(|e| {
+                    de::Error::custom(format!("deserialize {} failed, reason: {}", $expect, e))
+                })
+            }
+        }
+
+        impl<'de> DeserializeSeed<'de> for $seed {
+            type Value = $ty;
+            fn deserialize<D>(self, deserializer: D) -> Result<Self::Value, D::Error>
+            where
+                D: Deserializer<'de>,
+            {
+                deserializer.deserialize_str(self)
+            }
+        }
+    };
+}
+
+gen_precise!(
+    PreciseTimestamp,
+    NaiveDateTime,
+    PreciseTimestampSeed,
+    PrestoTy::Timestamp(None),
+    "%Y-%m-%d %H:%M:%S",
+    "TIMESTAMP",
+    NaiveDate::from_ymd_opt(1970, 1, 1)
+        .unwrap()
+        .and_hms_opt(0, 0, 0)
+        .unwrap(),
+    "precise timestamp"
+);
+gen_precise!(
+    PreciseTime,
+    NaiveTime,
+    PreciseTimeSeed,
+    PrestoTy::Time(None),
+    "%H:%M:%S",
+    "TIME",
+    NaiveTime::from_hms_opt(0, 0, 0).unwrap(),
+    "precise time"
+);
+
+// return (picoseconds, precision)
+pub(super) fn parse_fraction(s: &str) -> Result<(u64, usize), Error> {
+    let precision = s.len();
+    if precision > MAX_PRECISION {
+        return Err(Error::InvalidPrecision(precision));
+    }
+    if !s.bytes().all(|b| b.is_ascii_digit()) {
+        return Err(Error::ParseDateTimeFailed(format!(
+            "invalid fraction: {}",
+            s
+        )));
+    }
+    let digits: u64 = if s.is_empty() { 0 } else { s.parse().unwrap() };
+    let picos = digits * 10u64.pow((MAX_PRECISION - precision) as u32);
+    Ok((picos, precision))
+}
+
+pub(super) fn format_fraction(picos: u64, precision: usize) -> String {
+    if precision == 0 {
+        String::new()
+    } else {
+        let digits = picos / 10u64.pow((MAX_PRECISION - precision) as u32);
+        format!(".{:0width$}", digits, width = precision)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_round_trip() {
+        let data = [
+            "2001-08-22 03:04:05",
+            "2001-08-22 03:04:05.1",
+            "2001-08-22 03:04:05.123456789",
+            "2001-08-22 03:04:05.123456789012",
+            "2001-08-22 03:04:05.000000000000",
+        ];
+        for s in data {
+            let d = PreciseTimestamp::from_str(s).unwrap();
+            assert_eq!(d.to_string(), s);
+        }
+
+        let d = PreciseTimestamp::from_str("2001-08-22 03:04:05.123456789012").unwrap();
+        assert_eq!(d.precision(), 12);
+        assert_eq!(d.picoseconds(), 123_456_789_012);
+        assert_eq!(d.to_chrono().nanosecond(), 123_456_789);
+
+        let t = PreciseTime::from_str("01:02:03.000000000001").unwrap();
+        assert_eq!(t.picoseconds(), 1);
+        assert_eq!(t.sql_literal(), "TIME '01:02:03.000000000001'");
+
+        assert!(PreciseTime::from_str("01:02:03.0000000000001").is_err());
+    }
+
+    #[test]
+    fn test_new() {
+        let t = NaiveTime::from_hms_opt(1, 2, 3).unwrap();
+        let d = PreciseTime::new(t, 123_456_789_012, 6).unwrap();
+        assert_eq!(d.to_string(), "01:02:03.123456");
+        assert!(PreciseTime::new(t, 0, 13).is_err());
+        assert!(matches!(
+            PreciseTime::new(t, 1_000_000_000_000, 12),
+            Err(Error::FractionOutOfRange(_))
+        ));
+    }
+}
@@ -0,0 +1,66 @@
+use serde::de::{Deserialize, DeserializeSeed, Deserializer};
+use serde_json::Value;
+
+use super::{Context, Presto, PrestoTy};
+
+/// A value of any type kept as json together with the type presto provided, for types this
+/// crate can not decode.
+#[derive(Debug, PartialEq, Clone)]
+pub struct RawValue {
+    ty: PrestoTy,
+    value: Value,
+}
+
+impl RawValue {
+    pub fn new(ty: PrestoTy, value: Value) -> Self {
+        RawValue { ty, value }
+    }
+
+    pub fn ty(&self) -> &PrestoTy {
+        &self.ty
+    }
+
+    pub fn as_json(&self) -> &Value {
+        &self.value
+    }
+
+    pub fn into_json(self) -> Value {
+        self.value
+    }
+}
+
+impl Presto for RawValue {
+    type ValueType<'a> = &'a Value;
+    type Seed<'a, 'de> = RawValueSeed<'a>;
+
+    fn value(&self) -> Self::ValueType<'_> {
+        &self.value
+    }
+
+    fn ty() -> PrestoTy {
+        PrestoTy::Unknown
+    }
+
+    fn seed<'a, 'de>(ctx: &'a Context) -> Self::Seed<'a, 'de> {
+        RawValueSeed { ty: ctx.ty() }
+    }
+
+    fn empty() -> Self {
+        RawValue::new(PrestoTy::Unknown, Value::Null)
+    }
+}
+
+pub struct RawValueSeed<'a> {
+    ty: &'a PrestoTy,
+}
+
+impl<'a, 'de> DeserializeSeed<'de> for RawValueSeed<'a> {
+    type Value = RawValue;
+    fn deserialize<D>(self, deserializer: D) -> Result<Self::Value, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        let value = Value::deserialize(deserializer)?;
+        Ok(RawValue::new(self.ty.clone(), value))
+    }
+}
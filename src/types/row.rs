@@ -1,22 +1,118 @@
-use serde::de::{Deserialize, DeserializeSeed, Deserializer};
-use serde_json::Value;
+use std::fmt;
+use std::sync::Arc;
 
+use serde::de::{self, DeserializeSeed, Deserializer, SeqAccess, Visitor};
+use serde_json::{Map, Value};
+
+use super::util::column_index;
+use super::{Error, PrestoValue};
 use crate::{Context, Presto, PrestoTy};
 
-#[derive(Debug, Clone)]
+/// A row of any columns, the values are decoded with the column types presto provides.
+#[derive(Debug, PartialEq, Clone)]
 pub struct Row {
-    data: Vec<Value>,
+    data: Vec<PrestoValue>,
+    columns: Arc<[(String, PrestoTy)]>,
 }
 
 impl Row {
-    pub fn into_json(self) -> Vec<Value> {
+    pub(super) fn new(data: Vec<PrestoValue>, columns: Arc<[(String, PrestoTy)]>) -> Self {
+        Row { data, columns }
+    }
+
+    /// names and types of the columns, they are shared by all rows of a query
+    pub fn columns(&self) -> &[(String, PrestoTy)] {
+        &self.columns
+    }
+
+    pub fn values(&self) -> &[PrestoValue] {
+        &self.data
+    }
+
+    pub fn into_values(self) -> Vec<PrestoValue> {
         self.data
     }
+
+    pub fn len(&self) -> usize {
+        self.data.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.data.is_empty()
+    }
+
+    /// names are matched case insensitively when there is no exact match
+    pub fn index_of(&self, name: &str) -> Option<usize> {
+        column_index(&self.columns, name)
+    }
+
+    /// decode the `idx`th value as `T`, which is checked against the column type
+    pub fn get<T: Presto>(&self, idx: usize) -> Result<T, Error> {
+        match (self.data.get(idx), self.columns.get(idx)) {
+            (Some(value), Some((_, ty))) => decode(value, ty),
+            _ => Err(Error::ColumnNotFound(idx.to_string())),
+        }
+    }
+
+    pub fn get_by_name<T: Presto>(&self, name: &str) -> Result<T, Error> {
+        match self.index_of(name) {
+            Some(idx) => self.get(idx),
+            None => Err(Error::ColumnNotFound(name.to_string())),
+        }
+    }
+
+    /// values in the form presto sends them, map keys are rendered with `PrestoValue::to_text`
+    pub fn into_json(self) -> Result<Vec<Value>, Error> {
+        self.data.iter().map(|v| to_json(v, false)).collect()
+    }
+
+    /// values keyed by the column names, nested rows become objects as well
+    pub fn to_json_object(&self) -> Result<Map<String, Value>, Error> {
+        self.columns
+            .iter()
+            .zip(&self.data)
+            .map(|((name, _), v)| Ok((name.clone(), to_json(v, true)?)))
+            .collect()
+    }
+}
+
+// json objects only have string keys, so map keys are rendered as text. nested rows are
+// objects keyed by their column names if `named`, arrays otherwise.
+fn to_json(value: &PrestoValue, named: bool) -> Result<Value, Error> {
+    match value {
+        PrestoValue::Row(row) if named => row.to_json_object().map(Value::Object),
+        PrestoValue::Row(row) => row
+            .data
+            .iter()
+            .map(|v| to_json(v, named))
+            .collect::<Result<_, _>>()
+            .map(Value::Array),
+        PrestoValue::Array(v) => v
+            .iter()
+            .map(|v| to_json(v, named))
+            .collect::<Result<_, _>>()
+            .map(Value::Array),
+        PrestoValue::Map(v) => v
+            .iter()
+            .map(|(k, v)| Ok((k.to_text(), to_json(v, named)?)))
+            .collect::<Result<_, _>>()
+            .map(Value::Object),
+        v => serde_json::to_value(v).map_err(|e| Error::ConvertToJsonFailed(e.to_string())),
+    }
+}
+
+// values are decoded again from their wire form, so every `Presto` type can be a target
+fn decode<T: Presto>(value: &PrestoValue, ty: &PrestoTy) -> Result<T, Error> {
+    let ctx = Context::new::<T>(ty)?;
+    let json = serde_json::to_value(value).map_err(|e| Error::DecodeValueFailed(e.to_string()))?;
+    T::seed(&ctx)
+        .deserialize(json)
+        .map_err(|e| Error::DecodeValueFailed(e.to_string()))
 }
 
 impl Presto for Row {
-    type ValueType<'a> = &'a [Value];
-    type Seed<'a, 'de> = RowSeed;
+    type ValueType<'a> = &'a [PrestoValue];
+    type Seed<'a, 'de> = RowSeed<'a>;
 
     fn value(&self) -> Self::ValueType<'_> {
         &self.data
@@ -26,24 +122,68 @@ impl Presto for Row {
         PrestoTy::Unknown
     }
 
-    fn seed<'a, 'de>(_ctx: &'a Context) -> Self::Seed<'a, 'de> {
-        RowSeed
+    fn seed<'a, 'de>(ctx: &'a Context) -> Self::Seed<'a, 'de> {
+        RowSeed { ctx }
     }
 
     fn empty() -> Self {
-        Row { data: vec![] }
+        Row {
+            data: vec![],
+            columns: Arc::new([]),
+        }
     }
 }
 
-pub struct RowSeed;
+pub struct RowSeed<'a> {
+    ctx: &'a Context<'a>,
+}
+
+impl<'a, 'de> Visitor<'de> for RowSeed<'a> {
+    type Value = Row;
+    fn expecting(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+        formatter.write_str("row")
+    }
+
+    fn visit_seq<A>(self, mut seq: A) -> Result<Self::Value, A::Error>
+    where
+        A: SeqAccess<'de>,
+    {
+        let columns = match self.ctx.columns() {
+            Some(columns) => columns.clone(),
+            None => {
+                return Err(de::Error::custom(format!(
+                    "expected a row, found {}",
+                    self.ctx.ty().full_type()
+                )))
+            }
+        };
+
+        let mut data = Vec::with_capacity(columns.len());
+        for idx in 0..columns.len() {
+            let ty = self.ctx.ty().field_ty(idx).expect("invalid context");
+            let ctx = self.ctx.with_ty(ty);
+            match seq.next_element_seed(PrestoValue::seed(&ctx))? {
+                Some(v) => data.push(v),
+                None => return Err(de::Error::invalid_length(idx, &self)),
+            }
+        }
+
+        if let Ok(None) = seq.next_element::<de::IgnoredAny>() {
+            Ok(Row { data, columns })
+        } else {
+            Err(de::Error::custom(
+                "access seq failed, there are some extra data",
+            ))
+        }
+    }
+}
 
-impl<'de> DeserializeSeed<'de> for RowSeed {
+impl<'a, 'de> DeserializeSeed<'de> for RowSeed<'a> {
     type Value = Row;
     fn deserialize<D>(self, deserializer: D) -> Result<Self::Value, D::Error>
     where
         D: Deserializer<'de>,
     {
-        let data = <Vec<Value>>::deserialize(deserializer)?;
-        Ok(Row { data })
+        deserializer.deserialize_seq(self)
     }
 }
@@ -0,0 +1,73 @@
+use rust_decimal::Decimal;
+use serde::de::{self, DeserializeSeed, Deserializer};
+
+use super::dyn_decimal::BigDecimalVisitor;
+use super::{Context, Presto, PrestoTy, SqlLiteral};
+
+// `rust_decimal` keeps at most 28 fractional digits in a 96 bit mantissa, values which do not
+// fit are rejected instead of being rounded.
+impl Presto for Decimal {
+    type ValueType<'a> = String;
+    type Seed<'a, 'de> = RustDecimalSeed;
+
+    fn value(&self) -> Self::ValueType<'_> {
+        self.to_string()
+    }
+    fn ty() -> PrestoTy {
+        PrestoTy::DynDecimal
+    }
+    fn seed<'a, 'de>(_ctx: &'a Context) -> Self::Seed<'a, 'de> {
+        RustDecimalSeed
+    }
+
+    fn empty() -> Self {
+        Decimal::ZERO
+    }
+}
+
+impl SqlLiteral for Decimal {
+    fn sql_literal(&self) -> String {
+        format!("DECIMAL '{}'", self)
+    }
+}
+
+pub struct RustDecimalSeed;
+
+impl<'de> DeserializeSeed<'de> for RustDecimalSeed {
+    type Value = Decimal;
+    fn deserialize<D>(self, deserializer: D) -> Result<Self::Value, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        let mut d = deserializer.deserialize_any(BigDecimalVisitor)?;
+        // columns with a wider scale are fine as long as the extra digits are zeros
+        let max_scale = Decimal::MAX_SCALE as i64;
+        if d.as_bigint_and_exponent().1 > max_scale {
+            let rescaled = d.with_scale(max_scale);
+            if rescaled != d {
+                return Err(de::Error::custom(format!(
+                    "{} has more fractional digits than rust_decimal keeps",
+                    d
+                )));
+            }
+            d = rescaled;
+        }
+        Decimal::from_str_exact(&d.to_string()).map_err(|e| {
+            de::Error::custom(format!("deserialize rust_decimal failed, reason: {}", e))
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_range() {
+        let d = Decimal::from_str_exact("1123412341234123412.2222222220").unwrap();
+        assert_eq!(d.value(), "1123412341234123412.2222222220");
+        assert_eq!(d.sql_literal(), "DECIMAL '1123412341234123412.2222222220'");
+
+        assert!(Decimal::from_str_exact("1123412341234123412341234.2222222220").is_err());
+    }
+}
@@ -0,0 +1,105 @@
+use std::fmt;
+use std::marker::PhantomData;
+use std::ops::Deref;
+
+use serde::de::{self, DeserializeOwned, DeserializeSeed, Deserializer, SeqAccess, Visitor};
+
+use super::{Context, Error, Presto, PrestoTy, PrestoValue, Row};
+
+/// Decodes a row with `serde::Deserialize`. The row is turned into a map keyed by the column
+/// names, so `#[serde(rename, default, flatten)]` work as usual. Values keep the form presto
+/// sends, e.g. decimals and dates are strings. The decoded `Row` is kept and written back as
+/// is, so `T` does not need `Serialize`.
+#[derive(Debug, PartialEq, Clone)]
+pub struct Serde<T> {
+    // `None` only for the placeholder of `Presto::empty`, which is replaced when decoding
+    value: Option<T>,
+    row: Row,
+}
+
+impl<T> Serde<T> {
+    pub fn into_inner(self) -> T {
+        self.value.expect("decoded value")
+    }
+
+    /// the row `T` is decoded from
+    pub fn row(&self) -> &Row {
+        &self.row
+    }
+}
+
+impl<T: DeserializeOwned> Serde<T> {
+    fn decode(row: Row) -> Result<Self, Error> {
+        let map = row.to_json_object()?;
+        let value = T::deserialize(&map).map_err(|e| Error::DecodeValueFailed(e.to_string()))?;
+        Ok(Serde {
+            value: Some(value),
+            row,
+        })
+    }
+}
+
+impl<T> Deref for Serde<T> {
+    type Target = T;
+
+    fn deref(&self) -> &T {
+        self.value.as_ref().expect("decoded value")
+    }
+}
+
+impl<T: DeserializeOwned> Presto for Serde<T> {
+    type ValueType<'a> = &'a [PrestoValue] where T: 'a;
+    type Seed<'a, 'de> = SerdeSeed<'a, T>;
+
+    fn value(&self) -> Self::ValueType<'_> {
+        self.row.value()
+    }
+
+    fn ty() -> PrestoTy {
+        PrestoTy::Unknown
+    }
+
+    fn seed<'a, 'de>(ctx: &'a Context<'a>) -> Self::Seed<'a, 'de> {
+        SerdeSeed {
+            ctx,
+            _marker: PhantomData,
+        }
+    }
+
+    fn empty() -> Self {
+        Serde {
+            value: None,
+            row: Row::empty(),
+        }
+    }
+}
+
+pub struct SerdeSeed<'a, T> {
+    ctx: &'a Context<'a>,
+    _marker: PhantomData<T>,
+}
+
+impl<'a, 'de, T: DeserializeOwned> Visitor<'de> for SerdeSeed<'a, T> {
+    type Value = Serde<T>;
+    fn expecting(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+        formatter.write_str("row")
+    }
+
+    fn visit_seq<A>(self, seq: A) -> Result<Self::Value, A::Error>
+    where
+        A: SeqAccess<'de>,
+    {
+        let row = Row::seed(self.ctx).visit_seq(seq)?;
+        Serde::decode(row).map_err(de::Error::custom)
+    }
+}
+
+impl<'a, 'de, T: DeserializeOwned> DeserializeSeed<'de> for SerdeSeed<'a, T> {
+    type Value = Serde<T>;
+    fn deserialize<D>(self, deserializer: D) -> Result<Self::Value, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        deserializer.deserialize_seq(self)
+    }
+}
@@ -0,0 +1,179 @@
+use std::fmt;
+use std::marker::PhantomData;
+
+use serde::de::{self, DeserializeSeed, Deserializer, Visitor};
+
+use super::binary::{decode_base64, encode_base64, hex_literal};
+use super::{Context, Presto, PrestoTy, SqlLiteral};
+
+// Sketches are opaque to the client, we only keep the serialized bytes so they can be
+// stored and merged later by presto.
+macro_rules! gen_sketch {
+    ($ty:ident, $pty:expr, $expect:expr) => {
+        #[derive(Debug, Default, Eq, PartialEq, Hash, Clone)]
+        pub struct $ty {
+            inner: Vec<u8>,
+        }
+
+        impl $ty {
+            pub fn from_bytes(inner: Vec<u8>) -> Self {
+                $ty { inner }
+            }
+
+            pub fn as_bytes(&self) -> &[u8] {
+                &self.inner
+            }
+
+            pub fn into_bytes(self) -> Vec<u8> {
+                self.inner
+            }
+        }
+
+        impl Presto for $ty {
+            type ValueType<'a> = String;
+            type Seed<'a, 'de> = SketchSeed<$ty>;
+
+            fn value(&self) -> Self::ValueType<'_> {
+                encode_base64(&self.inner)
+            }
+
+            fn ty() -> PrestoTy {
+                $pty
+            }
+
+            fn seed<'a, 'de>(_ctx: &'a Context) -> Self::Seed<'a, 'de> {
+                SketchSeed::new($expect, $ty::from_bytes)
+            }
+
+            fn empty() -> Self {
+                Default::default()
+            }
+        }
+
+        impl SqlLiteral for $ty {
+            fn sql_literal(&self) -> String {
+                cast_literal(&self.inner, &Self::ty())
+            }
+        }
+    };
+}
+
+gen_sketch!(HyperLogLog, PrestoTy::HyperLogLog, "HyperLogLog");
+gen_sketch!(P4HyperLogLog, PrestoTy::P4HyperLogLog, "P4HyperLogLog");
+gen_sketch!(TDigest, PrestoTy::TDigest, "tdigest");
+gen_sketch!(SetDigest, PrestoTy::SetDigest, "SetDigest");
+
+/// `qdigest(T)`, `T` is the type of the values which are summarized, e.g. `QDigest<i64>`
+/// for `qdigest(bigint)`.
+#[derive(Debug, Eq, PartialEq, Hash, Clone)]
+pub struct QDigest<T> {
+    inner: Vec<u8>,
+    _marker: PhantomData<T>,
+}
+
+impl<T> QDigest<T> {
+    pub fn from_bytes(inner: Vec<u8>) -> Self {
+        QDigest {
+            inner,
+            _marker: PhantomData,
+        }
+    }
+
+    pub fn as_bytes(&self) -> &[u8] {
+        &self.inner
+    }
+
+    pub fn into_bytes(self) -> Vec<u8> {
+        self.inner
+    }
+}
+
+impl<T> Default for QDigest<T> {
+    fn default() -> Self {
+        QDigest::from_bytes(vec![])
+    }
+}
+
+impl<T: Presto> Presto for QDigest<T> {
+    type ValueType<'a> = String where T: 'a;
+    type Seed<'a, 'de> = SketchSeed<QDigest<T>>;
+
+    fn value(&self) -> Self::ValueType<'_> {
+        encode_base64(&self.inner)
+    }
+
+    fn ty() -> PrestoTy {
+        PrestoTy::QDigest(Box::new(T::ty()))
+    }
+
+    fn seed<'a, 'de>(_ctx: &'a Context) -> Self::Seed<'a, 'de> {
+        SketchSeed::new("qdigest", QDigest::from_bytes)
+    }
+
+    fn empty() -> Self {
+        Default::default()
+    }
+}
+
+impl<T: Presto> SqlLiteral for QDigest<T> {
+    fn sql_literal(&self) -> String {
+        cast_literal(&self.inner, &Self::ty())
+    }
+}
+
+fn cast_literal(data: &[u8], ty: &PrestoTy) -> String {
+    format!("CAST({} AS {})", hex_literal(data), ty.full_type())
+}
+
+pub struct SketchSeed<T> {
+    expect: &'static str,
+    from_bytes: fn(Vec<u8>) -> T,
+}
+
+impl<T> SketchSeed<T> {
+    fn new(expect: &'static str, from_bytes: fn(Vec<u8>) -> T) -> Self {
+        SketchSeed { expect, from_bytes }
+    }
+}
+
+impl<'de, T> Visitor<'de> for SketchSeed<T> {
+    type Value = T;
+
+    fn expecting(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+        write!(formatter, "base64 encoded {}", self.expect)
+    }
+
+    fn visit_str<E>(self, v: &str) -> Result<Self::Value, E>
+    where
+        E: de::Error,
+    {
+        let data = decode_base64(v).map_err(|e| {
+            de::Error::custom(format!("deserialize {} failed, reason: {}", self.expect, e))
+        })?;
+        Ok((self.from_bytes)(data))
+    }
+}
+
+impl<'de, T> DeserializeSeed<'de> for SketchSeed<T> {
+    type Value = T;
+    fn deserialize<D>(self, deserializer: D) -> Result<Self::Value, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        deserializer.deserialize_str(self)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_literal() {
+        let h = HyperLogLog::from_bytes(vec![2, 12, 255]);
+        assert_eq!(h.sql_literal(), "CAST(X'020CFF' AS HyperLogLog)");
+
+        let q = QDigest::<i64>::from_bytes(vec![0]);
+        assert_eq!(q.sql_literal(), "CAST(X'00' AS qdigest(bigint))");
+    }
+}
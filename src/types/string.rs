@@ -10,7 +10,7 @@ impl Presto for String {
         self
     }
     fn ty() -> PrestoTy {
-        PrestoTy::Varchar
+        PrestoTy::Varchar(None)
     }
     fn seed<'a, 'de>(_ctx: &'a Context) -> Self::Seed<'a, 'de> {
         StringSeed
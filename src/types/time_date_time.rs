@@ -0,0 +1,140 @@
+use chrono::{
+    DateTime, Datelike, FixedOffset, NaiveDate, NaiveDateTime, NaiveTime, TimeZone, Timelike,
+};
+use time::error::ComponentRange;
+use time::{Date, OffsetDateTime, PrimitiveDateTime, Time, UtcOffset};
+
+use super::{DateTimeWithZoneSeed, NaiveDateSeed, NaiveDateTimeSeed, NaiveTimeSeed};
+
+gen_proxy!(
+    Date,
+    TimeDateSeed,
+    NaiveDate,
+    NaiveDateSeed,
+    date_to_chrono,
+    date_from_chrono,
+    "time date"
+);
+gen_proxy!(
+    Time,
+    TimeTimeSeed,
+    NaiveTime,
+    NaiveTimeSeed,
+    time_to_chrono,
+    time_from_chrono,
+    "time time"
+);
+gen_proxy!(
+    PrimitiveDateTime,
+    TimePrimitiveDateTimeSeed,
+    NaiveDateTime,
+    NaiveDateTimeSeed,
+    date_time_to_chrono,
+    date_time_from_chrono,
+    "time primitive date time"
+);
+gen_proxy!(
+    OffsetDateTime,
+    TimeOffsetDateTimeSeed,
+    DateTime<FixedOffset>,
+    DateTimeWithZoneSeed,
+    offset_date_time_to_chrono,
+    offset_date_time_from_chrono,
+    "time offset date time"
+);
+
+// chrono supports a wider range of dates than `time`, so only the conversions from chrono
+// can fail.
+
+fn date_to_chrono(d: &Date) -> NaiveDate {
+    NaiveDate::from_yo_opt(d.year(), d.ordinal() as u32).unwrap()
+}
+
+fn date_from_chrono(d: NaiveDate) -> Result<Date, ComponentRange> {
+    Date::from_ordinal_date(d.year(), d.ordinal() as u16)
+}
+
+fn time_to_chrono(t: &Time) -> NaiveTime {
+    NaiveTime::from_hms_nano_opt(
+        t.hour() as u32,
+        t.minute() as u32,
+        t.second() as u32,
+        t.nanosecond(),
+    )
+    .unwrap()
+}
+
+fn time_from_chrono(t: NaiveTime) -> Result<Time, ComponentRange> {
+    Time::from_hms_nano(
+        t.hour() as u8,
+        t.minute() as u8,
+        t.second() as u8,
+        t.nanosecond(),
+    )
+}
+
+fn date_time_to_chrono(d: &PrimitiveDateTime) -> NaiveDateTime {
+    date_to_chrono(&d.date()).and_time(time_to_chrono(&d.time()))
+}
+
+fn date_time_from_chrono(d: NaiveDateTime) -> Result<PrimitiveDateTime, ComponentRange> {
+    Ok(PrimitiveDateTime::new(
+        date_from_chrono(d.date())?,
+        time_from_chrono(d.time())?,
+    ))
+}
+
+// `time` allows offsets up to 25:59:59 but chrono's are less than a day, such date times are
+// converted to UTC, which keeps the instant
+fn offset_date_time_to_chrono(d: &OffsetDateTime) -> DateTime<FixedOffset> {
+    match FixedOffset::east_opt(d.offset().whole_seconds()) {
+        Some(offset) => {
+            let local = date_to_chrono(&d.date()).and_time(time_to_chrono(&d.time()));
+            offset.from_local_datetime(&local).unwrap()
+        }
+        None => offset_date_time_to_chrono(&d.to_offset(UtcOffset::UTC)),
+    }
+}
+
+fn offset_date_time_from_chrono(
+    d: DateTime<FixedOffset>,
+) -> Result<OffsetDateTime, ComponentRange> {
+    let offset = UtcOffset::from_whole_seconds(d.offset().local_minus_utc())?;
+    Ok(date_time_from_chrono(d.naive_local())?.assume_offset(offset))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::types::{Presto, SqlLiteral};
+
+    #[test]
+    fn test_round_trip() {
+        let d = date_time_from_chrono(
+            NaiveDate::from_ymd_opt(2001, 8, 22)
+                .unwrap()
+                .and_hms_nano_opt(3, 4, 5, 123456789)
+                .unwrap(),
+        )
+        .unwrap();
+        assert_eq!(d.value(), "2001-08-22 03:04:05.123456789");
+        assert_eq!(d.sql_literal(), "TIMESTAMP '2001-08-22 03:04:05.123456789'");
+
+        let t = Time::from_hms_milli(1, 2, 3, 456).unwrap();
+        assert_eq!(t.value(), "01:02:03.456");
+
+        let o = d.assume_offset(UtcOffset::from_hms(8, 0, 0).unwrap());
+        assert_eq!(o.value(), "2001-08-22 03:04:05.123456789 +08:00");
+        assert_eq!(
+            offset_date_time_from_chrono(offset_date_time_to_chrono(&o)).unwrap(),
+            o
+        );
+
+        let o = OffsetDateTime::UNIX_EPOCH.to_offset(UtcOffset::from_hms(25, 0, 0).unwrap());
+        assert_eq!(o.value(), "1970-01-01 00:00:00.000 +00:00");
+        assert_eq!(
+            offset_date_time_from_chrono(offset_date_time_to_chrono(&o)).unwrap(),
+            o
+        );
+    }
+}
@@ -0,0 +1,103 @@
+use std::fmt;
+use std::marker::PhantomData;
+
+use serde::de::{self, DeserializeSeed, Deserializer, SeqAccess, Visitor};
+
+use super::{Context, Presto, PrestoTy};
+use crate::tuples::*;
+
+// tuples are anonymous rows, they also match named rows positionally, so a tuple can be the
+// top level row of a query
+macro_rules! tuple_impls {
+    ($($len:expr => ($($n:tt $name:ident)+))+) => {
+        paste::paste! {
+            $(
+                impl<$($name: Presto),+> Presto for ($($name,)+) {
+                    type ValueType<'a> = [<Tuple $len>]<$($name::ValueType<'a>,)+> where $($name: 'a),+;
+                    type Seed<'a, 'de> = [<Tuple $len Seed>]<'a, $($name,)+>;
+
+                    fn value(&self) -> Self::ValueType<'_> {
+                        [<Tuple $len>]($(self.$n.value(),)+)
+                    }
+
+                    fn ty() -> PrestoTy {
+                        PrestoTy::Tuple(vec![$($name::ty(),)+])
+                    }
+
+                    fn seed<'a, 'de>(ctx: &'a Context) -> Self::Seed<'a, 'de> {
+                        [<Tuple $len Seed>] {
+                            ctx,
+                            _marker: PhantomData,
+                        }
+                    }
+
+                    fn empty() -> Self {
+                        ($($name::empty(),)+)
+                    }
+                }
+
+                pub struct [<Tuple $len Seed>]<'a, $($name,)+> {
+                    ctx: &'a Context<'a>,
+                    _marker: PhantomData<($($name,)+)>,
+                }
+
+                impl<'a, 'de, $($name: Presto),+> Visitor<'de> for [<Tuple $len Seed>]<'a, $($name,)+> {
+                    type Value = ($($name,)+);
+
+                    fn expecting(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+                        write!(formatter, "tuple of {} elements", $len)
+                    }
+
+                    fn visit_seq<A: SeqAccess<'de>>(self, mut seq: A) -> Result<Self::Value, A::Error> {
+                        let ret = ($(
+                            {
+                                let ty = self.ctx.ty().field_ty($n).expect("invalid context");
+                                let ctx = self.ctx.with_ty(ty);
+                                match seq.next_element_seed($name::seed(&ctx))? {
+                                    Some(v) => v,
+                                    None => return Err(de::Error::invalid_length($n, &self)),
+                                }
+                            },
+                        )+);
+
+                        if let Ok(None) = seq.next_element::<String>() {
+                            Ok(ret)
+                        } else {
+                            Err(de::Error::custom("access seq failed, there are some extra data"))
+                        }
+                    }
+                }
+
+                impl<'a, 'de, $($name: Presto),+> DeserializeSeed<'de> for [<Tuple $len Seed>]<'a, $($name,)+> {
+                    type Value = ($($name,)+);
+
+                    fn deserialize<D>(self, deserializer: D) -> Result<Self::Value, D::Error>
+                    where
+                        D: Deserializer<'de>,
+                    {
+                        deserializer.deserialize_seq(self)
+                    }
+                }
+            )+
+        }
+    }
+}
+
+tuple_impls! {
+    1 => (0 T0)
+    2 => (0 T0 1 T1)
+    3 => (0 T0 1 T1 2 T2)
+    4 => (0 T0 1 T1 2 T2 3 T3)
+    5 => (0 T0 1 T1 2 T2 3 T3 4 T4)
+    6 => (0 T0 1 T1 2 T2 3 T3 4 T4 5 T5)
+    7 => (0 T0 1 T1 2 T2 3 T3 4 T4 5 T5 6 T6)
+    8 => (0 T0 1 T1 2 T2 3 T3 4 T4 5 T5 6 T6 7 T7)
+    9 => (0 T0 1 T1 2 T2 3 T3 4 T4 5 T5 6 T6 7 T7 8 T8)
+    10 => (0 T0 1 T1 2 T2 3 T3 4 T4 5 T5 6 T6 7 T7 8 T8 9 T9)
+    11 => (0 T0 1 T1 2 T2 3 T3 4 T4 5 T5 6 T6 7 T7 8 T8 9 T9 10 T10)
+    12 => (0 T0 1 T1 2 T2 3 T3 4 T4 5 T5 6 T6 7 T7 8 T8 9 T9 10 T10 11 T11)
+    13 => (0 T0 1 T1 2 T2 3 T3 4 T4 5 T5 6 T6 7 T7 8 T8 9 T9 10 T10 11 T11 12 T12)
+    14 => (0 T0 1 T1 2 T2 3 T3 4 T4 5 T5 6 T6 7 T7 8 T8 9 T9 10 T10 11 T11 12 T12 13 T13)
+    15 => (0 T0 1 T1 2 T2 3 T3 4 T4 5 T5 6 T6 7 T7 8 T8 9 T9 10 T10 11 T11 12 T12 13 T13 14 T14)
+    16 => (0 T0 1 T1 2 T2 3 T3 4 T4 5 T5 6 T6 7 T7 8 T8 9 T9 10 T10 11 T11 12 T12 13 T13 14 T14 15 T15)
+}
@@ -1,6 +1,17 @@
 use serde::ser::{SerializeMap, SerializeSeq};
 use serde::{Serialize, Serializer};
 
+use super::PrestoTy;
+
+// trino lowercases unquoted identifiers, an exact match wins over a case insensitive one
+pub(super) fn column_index(columns: &[(String, PrestoTy)], name: &str) -> Option<usize> {
+    columns.iter().position(|(n, _)| n == name).or_else(|| {
+        columns
+            .iter()
+            .position(|(n, _)| n.eq_ignore_ascii_case(name))
+    })
+}
+
 // serialize iterator
 // https://github.com/serde-rs/serde/issues/571#issuecomment-252004224
 pub struct SerializeIterator<T: Serialize, I: Iterator<Item = T> + Clone> {
@@ -0,0 +1,326 @@
+use std::fmt;
+use std::net::IpAddr;
+
+use chrono::NaiveDate;
+use serde::de::{self, DeserializeSeed, Deserializer, MapAccess, Visitor};
+use serde::{Serialize, Serializer};
+use serde_json::Value;
+
+use super::util::SerializeVecMap;
+use super::{
+    Binary, BingTile, Context, DynDecimal, HyperLogLog, IntervalDayToSecond, IntervalYearToMonth,
+    IpPrefix, P4HyperLogLog, PreciseTime, PreciseTimestamp, Presto, PrestoFloat, PrestoInt,
+    PrestoMapKey, PrestoTy, QDigest, RawValue, Row, SetDigest, TDigest, ZonedTime, ZonedTimestamp,
+};
+
+/// A value of any presto type, decoded with the type presto provides for it.
+#[derive(Debug, PartialEq, Clone)]
+pub enum PrestoValue {
+    Null,
+    Boolean(bool),
+    TinyInt(i8),
+    SmallInt(i16),
+    Integer(i32),
+    BigInt(i64),
+    Real(f32),
+    Double(f64),
+    Decimal(DynDecimal),
+    Varchar(String),
+    Char(String),
+    VarBinary(Binary),
+    Date(NaiveDate),
+    Time(PreciseTime),
+    TimeWithTimeZone(ZonedTime),
+    Timestamp(PreciseTimestamp),
+    TimestampWithTimeZone(ZonedTimestamp),
+    IntervalYearToMonth(IntervalYearToMonth),
+    IntervalDayToSecond(IntervalDayToSecond),
+    Uuid(::uuid::Uuid),
+    IpAddress(IpAddr),
+    IpPrefix(IpPrefix),
+    Json(Value),
+    HyperLogLog(HyperLogLog),
+    P4HyperLogLog(P4HyperLogLog),
+    QDigest(QDigest<PrestoValue>),
+    TDigest(TDigest),
+    SetDigest(SetDigest),
+    // kept as WKT, the `geo` feature decodes them into `geo_types`
+    Geometry(String),
+    SphericalGeography(String),
+    BingTile(BingTile),
+    Array(Vec<PrestoValue>),
+    // presto maps can have keys which are not comparable, e.g. doubles
+    Map(Vec<(PrestoValue, PrestoValue)>),
+    Row(Row),
+    // types this crate does not know
+    Other(RawValue),
+}
+
+impl PrestoValue {
+    pub fn is_null(&self) -> bool {
+        matches!(self, PrestoValue::Null)
+    }
+
+    /// the text form presto sends, values which are not sent as strings are rendered as json
+    pub fn to_text(&self) -> String {
+        use PrestoValue::*;
+
+        match self {
+            Varchar(v) | Char(v) | Geometry(v) | SphericalGeography(v) => v.clone(),
+            Real(v) if !v.is_finite() => non_finite_text(*v as f64),
+            Double(v) if !v.is_finite() => non_finite_text(*v),
+            v => match serde_json::to_value(v) {
+                Ok(Value::String(s)) => s,
+                Ok(v) => v.to_string(),
+                Err(e) => e.to_string(),
+            },
+        }
+    }
+}
+
+// presto's text of `NaN` and infinities, json has no form for them
+fn non_finite_text(v: f64) -> String {
+    if v.is_nan() {
+        "NaN".into()
+    } else if v > 0.0 {
+        "Infinity".into()
+    } else {
+        "-Infinity".into()
+    }
+}
+
+// rendered the way the presto cli does, nested values are not quoted
+impl fmt::Display for PrestoValue {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        use PrestoValue::*;
+
+        match self {
+            Null => f.write_str("NULL"),
+            Array(v) => {
+                f.write_str("[")?;
+                for (i, v) in v.iter().enumerate() {
+                    if i > 0 {
+                        f.write_str(", ")?;
+                    }
+                    write!(f, "{}", v)?;
+                }
+                f.write_str("]")
+            }
+            Map(v) => {
+                f.write_str("{")?;
+                for (i, (k, v)) in v.iter().enumerate() {
+                    if i > 0 {
+                        f.write_str(", ")?;
+                    }
+                    write!(f, "{}={}", k, v)?;
+                }
+                f.write_str("}")
+            }
+            Row(row) => {
+                f.write_str("{")?;
+                for (i, ((name, _), v)) in row.columns().iter().zip(row.values()).enumerate() {
+                    if i > 0 {
+                        f.write_str(", ")?;
+                    }
+                    write!(f, "{}={}", name, v)?;
+                }
+                f.write_str("}")
+            }
+            v => f.write_str(&v.to_text()),
+        }
+    }
+}
+
+impl Default for PrestoValue {
+    fn default() -> Self {
+        PrestoValue::Null
+    }
+}
+
+// written back in the form presto sends
+impl Serialize for PrestoValue {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        use PrestoValue::*;
+
+        match self {
+            Null => serializer.serialize_none(),
+            Boolean(v) => v.value().serialize(serializer),
+            TinyInt(v) => v.value().serialize(serializer),
+            SmallInt(v) => v.value().serialize(serializer),
+            Integer(v) => v.value().serialize(serializer),
+            BigInt(v) => v.value().serialize(serializer),
+            Real(v) => v.value().serialize(serializer),
+            Double(v) => v.value().serialize(serializer),
+            Decimal(v) => v.value().serialize(serializer),
+            Varchar(v) | Char(v) | Geometry(v) | SphericalGeography(v) => v.serialize(serializer),
+            VarBinary(v) => v.value().serialize(serializer),
+            Date(v) => v.value().serialize(serializer),
+            Time(v) => v.value().serialize(serializer),
+            TimeWithTimeZone(v) => v.value().serialize(serializer),
+            Timestamp(v) => v.value().serialize(serializer),
+            TimestampWithTimeZone(v) => v.value().serialize(serializer),
+            IntervalYearToMonth(v) => v.value().serialize(serializer),
+            IntervalDayToSecond(v) => v.value().serialize(serializer),
+            Uuid(v) => v.value().serialize(serializer),
+            IpAddress(v) => v.value().serialize(serializer),
+            IpPrefix(v) => v.value().serialize(serializer),
+            Json(v) => v.serialize(serializer),
+            HyperLogLog(v) => v.value().serialize(serializer),
+            P4HyperLogLog(v) => v.value().serialize(serializer),
+            QDigest(v) => v.value().serialize(serializer),
+            TDigest(v) => v.value().serialize(serializer),
+            SetDigest(v) => v.value().serialize(serializer),
+            BingTile(v) => v.value().serialize(serializer),
+            Array(v) => v.serialize(serializer),
+            Map(v) => SerializeVecMap {
+                iter: v.iter().map(|(k, v)| (k, v)).collect(),
+            }
+            .serialize(serializer),
+            Row(v) => v.value().serialize(serializer),
+            Other(v) => v.value().serialize(serializer),
+        }
+    }
+}
+
+impl Presto for PrestoValue {
+    type ValueType<'a> = &'a PrestoValue;
+    type Seed<'a, 'de> = PrestoValueSeed<'a>;
+
+    fn value(&self) -> Self::ValueType<'_> {
+        self
+    }
+
+    fn ty() -> PrestoTy {
+        PrestoTy::Unknown
+    }
+
+    fn seed<'a, 'de>(ctx: &'a Context<'a>) -> Self::Seed<'a, 'de> {
+        PrestoValueSeed { ctx }
+    }
+
+    fn empty() -> Self {
+        PrestoValue::Null
+    }
+}
+
+impl PrestoMapKey for PrestoValue {}
+
+pub struct PrestoValueSeed<'a> {
+    ctx: &'a Context<'a>,
+}
+
+impl<'a, 'de> Visitor<'de> for PrestoValueSeed<'a> {
+    type Value = PrestoValue;
+    fn expecting(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+        formatter.write_str(&self.ctx.ty().full_type())
+    }
+
+    fn visit_none<E>(self) -> Result<Self::Value, E>
+    where
+        E: de::Error,
+    {
+        Ok(PrestoValue::Null)
+    }
+
+    fn visit_unit<E>(self) -> Result<Self::Value, E>
+    where
+        E: de::Error,
+    {
+        Ok(PrestoValue::Null)
+    }
+
+    fn visit_some<D>(self, deserializer: D) -> Result<Self::Value, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        use PrestoTy as T;
+        use PrestoValue as V;
+
+        let ctx = self.ctx;
+        let d = deserializer;
+        let ret = match ctx.ty() {
+            T::Boolean => V::Boolean(bool::seed(ctx).deserialize(d)?),
+            T::PrestoInt(PrestoInt::I8) => V::TinyInt(i8::seed(ctx).deserialize(d)?),
+            T::PrestoInt(PrestoInt::I16) => V::SmallInt(i16::seed(ctx).deserialize(d)?),
+            T::PrestoInt(PrestoInt::I32) => V::Integer(i32::seed(ctx).deserialize(d)?),
+            T::PrestoInt(PrestoInt::I64) => V::BigInt(i64::seed(ctx).deserialize(d)?),
+            T::PrestoFloat(PrestoFloat::F32) => V::Real(f32::seed(ctx).deserialize(d)?),
+            T::PrestoFloat(PrestoFloat::F64) => V::Double(f64::seed(ctx).deserialize(d)?),
+            T::Decimal(_, _) => V::Decimal(DynDecimal::seed(ctx).deserialize(d)?),
+            T::Varchar(_) => V::Varchar(String::seed(ctx).deserialize(d)?),
+            T::Char(_) => V::Char(String::seed(ctx).deserialize(d)?),
+            T::VarBinary => V::VarBinary(Binary::seed(ctx).deserialize(d)?),
+            T::Date => V::Date(NaiveDate::seed(ctx).deserialize(d)?),
+            T::Time(_) => V::Time(PreciseTime::seed(ctx).deserialize(d)?),
+            T::TimeWithTimeZone(_) => V::TimeWithTimeZone(ZonedTime::seed(ctx).deserialize(d)?),
+            T::Timestamp(_) => V::Timestamp(PreciseTimestamp::seed(ctx).deserialize(d)?),
+            T::TimestampWithTimeZone(_) => {
+                V::TimestampWithTimeZone(ZonedTimestamp::seed(ctx).deserialize(d)?)
+            }
+            T::IntervalYearToMonth => {
+                V::IntervalYearToMonth(IntervalYearToMonth::seed(ctx).deserialize(d)?)
+            }
+            T::IntervalDayToSecond => {
+                V::IntervalDayToSecond(IntervalDayToSecond::seed(ctx).deserialize(d)?)
+            }
+            T::Uuid => V::Uuid(::uuid::Uuid::seed(ctx).deserialize(d)?),
+            T::IpAddress => V::IpAddress(IpAddr::seed(ctx).deserialize(d)?),
+            T::IpPrefix => V::IpPrefix(IpPrefix::seed(ctx).deserialize(d)?),
+            T::Json => V::Json(Value::seed(ctx).deserialize(d)?),
+            T::HyperLogLog => V::HyperLogLog(HyperLogLog::seed(ctx).deserialize(d)?),
+            T::P4HyperLogLog => V::P4HyperLogLog(P4HyperLogLog::seed(ctx).deserialize(d)?),
+            T::QDigest(_) => V::QDigest(QDigest::seed(ctx).deserialize(d)?),
+            T::TDigest => V::TDigest(TDigest::seed(ctx).deserialize(d)?),
+            T::SetDigest => V::SetDigest(SetDigest::seed(ctx).deserialize(d)?),
+            T::Geometry => V::Geometry(String::seed(ctx).deserialize(d)?),
+            T::SphericalGeography => V::SphericalGeography(String::seed(ctx).deserialize(d)?),
+            T::BingTile => V::BingTile(BingTile::seed(ctx).deserialize(d)?),
+            T::Array(_) => V::Array(Vec::seed(ctx).deserialize(d)?),
+            T::Map(k, v) => V::Map(d.deserialize_map(MapSeed {
+                key: ctx.with_ty(k),
+                value: ctx.with_ty(v),
+            })?),
+            T::Tuple(_) | T::Row(_) => V::Row(Row::seed(ctx).deserialize(d)?),
+            _ => V::Other(RawValue::seed(ctx).deserialize(d)?),
+        };
+        Ok(ret)
+    }
+}
+
+impl<'a, 'de> DeserializeSeed<'de> for PrestoValueSeed<'a> {
+    type Value = PrestoValue;
+    fn deserialize<D>(self, deserializer: D) -> Result<Self::Value, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        deserializer.deserialize_option(self)
+    }
+}
+
+struct MapSeed<'a> {
+    key: Context<'a>,
+    value: Context<'a>,
+}
+
+impl<'a, 'de> Visitor<'de> for MapSeed<'a> {
+    type Value = Vec<(PrestoValue, PrestoValue)>;
+    fn expecting(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+        formatter.write_str("map")
+    }
+
+    fn visit_map<A>(self, mut map: A) -> Result<Self::Value, A::Error>
+    where
+        A: MapAccess<'de>,
+    {
+        let mut ret = vec![];
+        while let Some(entry) =
+            map.next_entry_seed(PrestoValue::seed(&self.key), PrestoValue::seed(&self.value))?
+        {
+            ret.push(entry);
+        }
+        Ok(ret)
+    }
+}
@@ -0,0 +1,54 @@
+use std::fmt;
+use std::marker::PhantomData;
+
+use serde::de::{self, DeserializeSeed, Deserializer};
+use serde::{Serialize, Serializer};
+
+use super::Presto;
+
+// helpers for `#[presto(with = "module")]`, the module provides a `Proxy` type which
+// implements `Presto` and the `to_presto` and `from_presto` conversions.
+
+/// Serialize an owned proxy value of a field.
+pub struct WithValue<P>(pub P);
+
+impl<P: Presto> Serialize for WithValue<P> {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        self.0.value().serialize(serializer)
+    }
+}
+
+/// Decode a proxy value and convert it into the field.
+pub struct WithSeed<S, P, T, E> {
+    inner: S,
+    from: fn(P) -> Result<T, E>,
+    _marker: PhantomData<P>,
+}
+
+impl<S, P, T, E> WithSeed<S, P, T, E> {
+    pub fn new(inner: S, from: fn(P) -> Result<T, E>) -> Self {
+        WithSeed {
+            inner,
+            from,
+            _marker: PhantomData,
+        }
+    }
+}
+
+impl<'de, S, P, T, E> DeserializeSeed<'de> for WithSeed<S, P, T, E>
+where
+    S: DeserializeSeed<'de, Value = P>,
+    E: fmt::Display,
+{
+    type Value = T;
+    fn deserialize<D>(self, deserializer: D) -> Result<Self::Value, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        let p = self.inner.deserialize(deserializer)?;
+        (self.from)(p).map_err(de::Error::custom)
+    }
+}
@@ -0,0 +1,388 @@
+use std::fmt;
+use std::str::FromStr;
+
+use chrono::{DateTime, FixedOffset, NaiveDate, NaiveTime, Offset, TimeZone, Timelike, Utc};
+use chrono_tz::Tz;
+use serde::de::{self, DeserializeSeed, Deserializer, Visitor};
+
+use super::date_time::{nano_fraction, NANO_PRECISION};
+use super::{Context, Error, PreciseTime, PreciseTimestamp, Presto, PrestoTy, SqlLiteral};
+
+/// The zone part of a `timestamp with time zone` value, presto sends either a zone id like
+/// `America/New_York` or a fixed offset like `+08:00`.
+#[derive(Debug, Eq, PartialEq, Hash, Clone, Copy)]
+pub enum Zone {
+    Named(Tz),
+    Offset(FixedOffset),
+}
+
+impl FromStr for Zone {
+    type Err = Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        if s.starts_with('+') || s.starts_with('-') {
+            FixedOffset::from_str(s)
+                .map(Zone::Offset)
+                .map_err(|e| Error::ParseDateTimeFailed(format!("{}", e)))
+        } else {
+            Tz::from_str(s)
+                .map(Zone::Named)
+                .map_err(Error::ParseDateTimeFailed)
+        }
+    }
+}
+
+impl fmt::Display for Zone {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Zone::Named(tz) => f.write_str(tz.name()),
+            Zone::Offset(offset) => write!(f, "{}", offset),
+        }
+    }
+}
+
+/// `timestamp with time zone` which keeps the zone as it is sent by presto.
+#[derive(Debug, Eq, PartialEq, Hash, Clone, Copy)]
+pub struct ZonedTimestamp {
+    local: PreciseTimestamp,
+    zone: Zone,
+}
+
+impl ZonedTimestamp {
+    pub fn new(local: PreciseTimestamp, zone: Zone) -> Self {
+        ZonedTimestamp { local, zone }
+    }
+
+    pub fn local(&self) -> &PreciseTimestamp {
+        &self.local
+    }
+
+    pub fn zone(&self) -> &Zone {
+        &self.zone
+    }
+
+    /// return `None` if the local time does not exist in the zone. presto does not send the
+    /// offset, so a local time which occurs twice, e.g. when daylight saving time ends, is
+    /// resolved to the earlier one.
+    pub fn to_date_time(&self) -> Option<DateTime<FixedOffset>> {
+        let naive = self.local.to_chrono();
+        match self.zone {
+            Zone::Named(tz) => tz
+                .from_local_datetime(&naive)
+                .earliest()
+                .map(|d| d.fixed_offset()),
+            Zone::Offset(offset) => offset.from_local_datetime(&naive).single(),
+        }
+    }
+}
+
+impl FromStr for ZonedTimestamp {
+    type Err = Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let (local, zone) = s
+            .rsplit_once(' ')
+            .ok_or_else(|| Error::ParseDateTimeFailed(format!("missing time zone: {}", s)))?;
+        Ok(ZonedTimestamp {
+            local: local.parse()?,
+            zone: zone.parse()?,
+        })
+    }
+}
+
+impl fmt::Display for ZonedTimestamp {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{} {}", self.local, self.zone)
+    }
+}
+
+/// `time with time zone`, presto only uses fixed offsets for it.
+#[derive(Debug, Eq, PartialEq, Hash, Clone, Copy)]
+pub struct ZonedTime {
+    local: PreciseTime,
+    offset: FixedOffset,
+}
+
+impl ZonedTime {
+    pub fn new(local: PreciseTime, offset: FixedOffset) -> Self {
+        ZonedTime { local, offset }
+    }
+
+    pub fn local(&self) -> &PreciseTime {
+        &self.local
+    }
+
+    pub fn offset(&self) -> &FixedOffset {
+        &self.offset
+    }
+
+    /// the time in UTC, truncated to nanoseconds
+    pub fn to_utc(&self) -> NaiveTime {
+        self.local.to_chrono() - self.offset
+    }
+}
+
+impl FromStr for ZonedTime {
+    type Err = Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let idx = s
+            .rfind(['+', '-'])
+            .ok_or_else(|| Error::ParseDateTimeFailed(format!("missing offset: {}", s)))?;
+        let (local, offset) = s.split_at(idx);
+        let offset = FixedOffset::from_str(offset)
+            .map_err(|e| Error::ParseDateTimeFailed(format!("{}", e)))?;
+        Ok(ZonedTime {
+            local: local.trim_end().parse()?,
+            offset,
+        })
+    }
+}
+
+impl fmt::Display for ZonedTime {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}{}", self.local, self.offset)
+    }
+}
+
+macro_rules! gen_zoned {
+    ($ty:ty, $seed:ident, $pty:expr, $literal:expr, $empty:expr, $expect:expr) => {
+        impl Presto for $ty {
+            type ValueType<'a> = String;
+            type Seed<'a, 'de> = $seed;
+
+            fn value(&self) -> Self::ValueType<'_> {
+                self.to_string()
+            }
+
+            fn ty() -> PrestoTy {
+                $pty
+            }
+
+            fn seed<'a, 'de>(_ctx: &'a Context) -> Self::Seed<'a, 'de> {
+                $seed
+            }
+
+            fn empty() -> Self {
+                $empty
+            }
+        }
+
+        impl SqlLiteral for $ty {
+            fn sql_literal(&self) -> String {
+                format!("{} '{}'", $literal, self)
+            }
+        }
+
+        pub struct $seed;
+
+        impl<'de> Visitor<'de> for $seed {
+            type Value = $ty;
+
+            fn expecting(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+                formatter.write_str($expect)
+            }
+
+            fn visit_str<E>(self, v: &str) -> Result<Self::Value, E>
+            where
+                E: de::Error,
+            {
+                <$ty>::from_str(v).map_err(|e| {
+                    de::Error::custom(format!("deserialize {} failed, reason: {}", $expect, e))
+                })
+            }
+        }
+
+        impl<'de> DeserializeSeed<'de> for $seed {
+            type Value = $ty;
+            fn deserialize<D>(self, deserializer: D) -> Result<Self::Value, D::Error>
+            where
+                D: Deserializer<'de>,
+            {
+                deserializer.deserialize_str(self)
+            }
+        }
+    };
+}
+
+gen_zoned!(
+    ZonedTimestamp,
+    ZonedTimestampSeed,
+    PrestoTy::TimestampWithTimeZone(None),
+    "TIMESTAMP",
+    ZonedTimestamp::new(PreciseTimestamp::empty(), Zone::Named(Tz::UTC)),
+    "zoned timestamp"
+);
+gen_zoned!(
+    ZonedTime,
+    ZonedTimeSeed,
+    PrestoTy::TimeWithTimeZone(None),
+    "TIME",
+    ZonedTime::new(PreciseTime::empty(), Utc.fix()),
+    "zoned time"
+);
+
+// chrono's `DateTime`s are converted from `ZonedTimestamp`, the zone is rendered in the
+// form the `DateTime` keeps it.
+macro_rules! gen_date_time_with_zone {
+    ($tz:ty, $seed:ident, $from:expr, $zone:expr, $empty:expr, $expect:expr) => {
+        impl Presto for DateTime<$tz> {
+            type ValueType<'a> = String;
+            type Seed<'a, 'de> = $seed;
+
+            fn value(&self) -> Self::ValueType<'_> {
+                format!(
+                    "{}{} {}",
+                    self.format("%Y-%m-%d %H:%M:%S"),
+                    nano_fraction(self.nanosecond()),
+                    $zone(self)
+                )
+            }
+
+            fn ty() -> PrestoTy {
+                PrestoTy::TimestampWithTimeZone(Some(NANO_PRECISION))
+            }
+
+            fn seed<'a, 'de>(_ctx: &'a Context) -> Self::Seed<'a, 'de> {
+                $seed
+            }
+
+            fn empty() -> Self {
+                $empty
+            }
+        }
+
+        impl SqlLiteral for DateTime<$tz> {
+            fn sql_literal(&self) -> String {
+                format!("TIMESTAMP '{}'", self.value())
+            }
+        }
+
+        pub struct $seed;
+
+        impl<'de> Visitor<'de> for $seed {
+            type Value = DateTime<$tz>;
+
+            fn expecting(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+                formatter.write_str($expect)
+            }
+
+            fn visit_str<E>(self, v: &str) -> Result<Self::Value, E>
+            where
+                E: de::Error,
+            {
+                ZonedTimestamp::from_str(v).and_then($from).map_err(|e| {
+                    de::Error::custom(format!("deserialize {} failed, reason: {}", $expect, e))
+                })
+            }
+        }
+
+        impl<'de> DeserializeSeed<'de> for $seed {
+            type Value = DateTime<$tz>;
+            fn deserialize<D>(self, deserializer: D) -> Result<Self::Value, D::Error>
+            where
+                D: Deserializer<'de>,
+            {
+                deserializer.deserialize_str(self)
+            }
+        }
+    };
+}
+
+gen_date_time_with_zone!(
+    FixedOffset,
+    DateTimeWithZoneSeed,
+    |z: ZonedTimestamp| z.to_date_time().ok_or_else(|| invalid_local(&z)),
+    |d: &DateTime<FixedOffset>| d.format("%:z"),
+    DateTime::from_naive_utc_and_offset(epoch(), Utc.fix()),
+    "date time with time zone"
+);
+gen_date_time_with_zone!(
+    Tz,
+    DateTimeWithTzSeed,
+    |z: ZonedTimestamp| {
+        let tz = match z.zone {
+            Zone::Named(tz) => tz,
+            Zone::Offset(offset) if offset.local_minus_utc() == 0 => Tz::UTC,
+            Zone::Offset(offset) => {
+                return Err(Error::ParseDateTimeFailed(format!(
+                    "offset {} has no time zone id",
+                    offset
+                )))
+            }
+        };
+        // ambiguous local times resolve to the earlier one, same as `to_date_time`
+        tz.from_local_datetime(&z.local.to_chrono())
+            .earliest()
+            .ok_or_else(|| invalid_local(&z))
+    },
+    |d: &DateTime<Tz>| d.timezone().name(),
+    Tz::UTC.from_utc_datetime(&epoch()),
+    "date time with time zone id"
+);
+gen_date_time_with_zone!(
+    Utc,
+    DateTimeUtcSeed,
+    |z: ZonedTimestamp| z
+        .to_date_time()
+        .map(|d| d.with_timezone(&Utc))
+        .ok_or_else(|| invalid_local(&z)),
+    |_: &DateTime<Utc>| "UTC",
+    Utc.from_utc_datetime(&epoch()),
+    "date time in utc"
+);
+
+fn epoch() -> chrono::NaiveDateTime {
+    NaiveDate::from_ymd_opt(1970, 1, 1)
+        .unwrap()
+        .and_hms_opt(0, 0, 0)
+        .unwrap()
+}
+
+fn invalid_local(z: &ZonedTimestamp) -> Error {
+    Error::ParseDateTimeFailed(format!("{} does not exist", z))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_zoned_timestamp() {
+        let s = "2024-01-01 10:00:00.000 America/New_York";
+        let z = ZonedTimestamp::from_str(s).unwrap();
+        assert_eq!(z.zone(), &Zone::Named(Tz::America__New_York));
+        assert_eq!(z.to_string(), s);
+        assert_eq!(
+            z.to_date_time().unwrap().to_rfc3339(),
+            "2024-01-01T10:00:00-05:00"
+        );
+
+        let z = ZonedTimestamp::from_str("2024-11-03 01:30:00 America/New_York").unwrap();
+        assert_eq!(
+            z.to_date_time().unwrap().to_rfc3339(),
+            "2024-11-03T01:30:00-04:00"
+        );
+        let z = ZonedTimestamp::from_str("2024-03-10 02:30:00 America/New_York").unwrap();
+        assert_eq!(z.to_date_time(), None);
+
+        let s = "2024-01-01 10:00:00.123456789012 +08:00";
+        let z = ZonedTimestamp::from_str(s).unwrap();
+        assert_eq!(z.to_string(), s);
+        assert_eq!(z.sql_literal(), format!("TIMESTAMP '{}'", s));
+    }
+
+    #[test]
+    fn test_zoned_time() {
+        let s = "01:02:03.456+08:00";
+        let t = ZonedTime::from_str(s).unwrap();
+        assert_eq!(t.to_string(), s);
+        assert_eq!(
+            t.to_utc(),
+            NaiveTime::from_hms_milli_opt(17, 2, 3, 456).unwrap()
+        );
+
+        let t = ZonedTime::from_str("01:02:03-01:00").unwrap();
+        assert_eq!(t.to_string(), "01:02:03-01:00");
+    }
+}
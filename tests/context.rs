@@ -1,6 +1,6 @@
 #![allow(incomplete_features)]
 
-use prusto::types::{Context, PrestoTy};
+use prusto::types::{Context, PrestoInt, PrestoTy};
 use prusto::Presto;
 
 #[derive(Presto)]
@@ -29,6 +29,13 @@ struct D {
     a: B,
 }
 
+#[derive(Presto)]
+struct E {
+    d: i32,
+    #[presto(flatten)]
+    a: A,
+}
+
 #[test]
 fn test_simple() {
     let provided = B::ty();
@@ -66,3 +73,38 @@ fn test_false() {
 
     assert!(res.is_err());
 }
+
+#[test]
+fn test_mismatch_path() {
+    let provided = PrestoTy::Row(vec![
+        (
+            "a".into(),
+            PrestoTy::Row(vec![
+                ("a".into(), PrestoTy::Varchar(None)),
+                ("b".into(), PrestoTy::Varchar(None)),
+                ("c".into(), PrestoTy::Varchar(None)),
+            ]),
+        ),
+        ("b".into(), PrestoTy::PrestoInt(PrestoInt::I8)),
+    ]);
+    let e = Context::new::<C>(&provided).unwrap_err();
+    assert_eq!(e.to_string(), "`a.b` expected integer, found varchar");
+
+    let provided = PrestoTy::Row(vec![("b".into(), PrestoTy::PrestoInt(PrestoInt::I64))]);
+    let e = Context::new::<C>(&provided).unwrap_err();
+    assert_eq!(e.to_string(), "`a` is missing");
+}
+
+#[test]
+fn test_flatten() {
+    let provided = PrestoTy::Row(vec![
+        ("c".into(), PrestoTy::Varchar(None)),
+        ("d".into(), PrestoTy::PrestoInt(PrestoInt::I32)),
+        ("a".into(), PrestoTy::Varchar(None)),
+        ("b".into(), PrestoTy::PrestoInt(PrestoInt::I32)),
+    ]);
+    let ctx = Context::new::<E>(&provided).unwrap();
+    let ret = ctx.row_map().unwrap();
+
+    assert_eq!(ret, &[3, 0, 1, 2]);
+}
@@ -6,13 +6,22 @@ use std::io::Read;
 use std::iter::FromIterator;
 use std::str::FromStr;
 
-use chrono::{DateTime, FixedOffset, NaiveDate, NaiveDateTime, NaiveTime};
+use bytes::Bytes;
+use chrono::{
+    DateTime, Duration, FixedOffset, NaiveDate, NaiveDateTime, NaiveTime, TimeZone, Timelike, Utc,
+};
+use chrono_tz::Tz;
 use maplit::{btreemap, hashmap};
 use serde_json::value::Value;
 
-use prusto::types::{DataSet, Decimal};
-use prusto::{Column, FixedChar, IntervalDayToSecond, IntervalYearToMonth, Row};
-use prusto::{Presto, PrestoFloat, PrestoInt, PrestoTy};
+use bigdecimal::BigDecimal;
+use prusto::types::{DataSet, Decimal, DynDecimal, Lenient};
+use prusto::{Binary, BingTile, Column, FixedChar, IntervalDayToSecond, IntervalYearToMonth, Row};
+use prusto::{
+    BoundedString, IpPrefix, Presto, PrestoFloat, PrestoInt, PrestoTy, PrestoValue, RawValue,
+};
+use prusto::{HyperLogLog, P4HyperLogLog, QDigest, SetDigest, SqlLiteral, TDigest};
+use prusto::{PreciseTime, PreciseTimestamp, Zone, ZonedTime, ZonedTimestamp};
 use std::net::IpAddr;
 use uuid::Uuid;
 
@@ -59,9 +68,29 @@ fn test_json() {
     let d = d.into_vec();
     assert_eq!(d.len(), 1);
     assert_eq!(
-        d[0].clone().into_json(),
+        d[0].clone().into_json().unwrap(),
         vec![Value::String("abc".to_string())]
     );
+
+    // json has no float keys, map keys are rendered as text
+    let (_, mut v) = read("map");
+    v["columns"][0]["type"] = Value::String("map(double,integer)".into());
+    v["columns"][0]["typeSignature"]["arguments"][0]["value"] = serde_json::json!({
+        "rawType": "double",
+        "arguments": []
+    });
+    v["data"][0][0] = serde_json::json!({"NaN": 1, "1.5": 2});
+    let d = serde_json::from_value::<DataSet<Row>>(v)
+        .unwrap()
+        .into_vec();
+    assert_eq!(
+        d[0].clone().into_json().unwrap()[0],
+        serde_json::json!({"NaN": 1, "1.5": 2})
+    );
+    assert_eq!(
+        d[0].to_json_object().unwrap()["a"],
+        serde_json::json!({"NaN": 1, "1.5": 2})
+    );
 }
 
 #[test]
@@ -80,6 +109,47 @@ fn test_char() {
     assert_eq!(d[0].a.clone().into_string(), "abc");
 }
 
+#[test]
+fn test_varchar() {
+    #[derive(Presto, Eq, PartialEq, Debug, Clone)]
+    struct A {
+        a: BoundedString<3>,
+    }
+
+    #[derive(Presto, Eq, PartialEq, Debug, Clone)]
+    struct B {
+        a: BoundedString<2>,
+    }
+
+    let (s, v) = read("varchar");
+    let d = serde_json::from_str::<DataSet<A>>(&s).unwrap();
+    assert_ds(d.clone(), v);
+
+    let d = d.into_vec();
+    assert_eq!(d[0].a.as_str(), "abc");
+
+    let e = serde_json::from_str::<DataSet<B>>(&s).unwrap_err();
+    assert!(
+        e.to_string()
+            .contains("`a` expected varchar(2), found varchar(3)"),
+        "{}",
+        e
+    );
+    let e = serde_json::from_str::<DataSet<Lenient<B>>>(&s).unwrap_err();
+    assert!(
+        e.to_string()
+            .contains("3 characters do not fit in varchar(2)"),
+        "{}",
+        e
+    );
+
+    let ty = PrestoTy::Varchar(Some(64)).into_type_signature();
+    assert_eq!(
+        PrestoTy::from_type_signature(ty).unwrap(),
+        PrestoTy::Varchar(Some(64))
+    );
+}
+
 #[test]
 fn test_interval_year_to_month() {
     #[derive(Presto, Eq, PartialEq, Debug, Clone)]
@@ -117,6 +187,47 @@ fn test_ip_address() {
     assert_eq!(d[0].a, IpAddr::from_str("10.0.0.1").unwrap());
 }
 
+#[test]
+fn test_ip_prefix() {
+    #[derive(Presto, Eq, PartialEq, Debug, Clone)]
+    struct A {
+        a: IpPrefix,
+        b: Vec<IpPrefix>,
+        c: HashMap<IpPrefix, i64>,
+    }
+
+    let (s, v) = read("ip_prefix");
+    let d = serde_json::from_str::<DataSet<A>>(&s).unwrap();
+    assert_ds(d.clone(), v);
+
+    let d = d.into_vec();
+    let a = IpPrefix::from_str("10.0.0.0/8").unwrap();
+    assert_eq!(d[0].a, a);
+    assert_eq!(d[0].b[1], IpPrefix::from_str("2001:db8::/32").unwrap());
+    assert_eq!(d[0].c[&a], 1);
+    assert_eq!(a.sql_literal(), "IPPREFIX '10.0.0.0/8'");
+}
+
+#[cfg(feature = "ipnet")]
+#[test]
+fn test_ipnet() {
+    use ipnet::IpNet;
+
+    #[derive(Presto, Eq, PartialEq, Debug, Clone)]
+    struct A {
+        a: IpNet,
+        b: Vec<IpNet>,
+        c: HashMap<IpNet, i64>,
+    }
+
+    let (s, v) = read("ip_prefix");
+    let d = serde_json::from_str::<DataSet<A>>(&s).unwrap();
+    assert_ds(d.clone(), v);
+
+    let d = d.into_vec();
+    assert_eq!(d[0].a, IpNet::from_str("10.0.0.0/8").unwrap());
+}
+
 #[test]
 fn test_uuid() {
     #[derive(Presto, Eq, PartialEq, Debug, Clone)]
@@ -160,6 +271,27 @@ fn test_interval_day_to_second() {
     assert_eq!(d[0].e.total_seconds(), 611);
 }
 
+#[test]
+fn test_interval_duration() {
+    #[derive(Presto, Eq, PartialEq, Debug, Clone)]
+    struct A {
+        a: Duration,
+        b: Duration,
+        c: Duration,
+        d: Duration,
+        e: Duration,
+    }
+
+    let (s, v) = read("interval_day_to_second");
+    let d = serde_json::from_str::<DataSet<A>>(&s).unwrap();
+    assert_ds(d.clone(), v);
+
+    let d = d.into_vec();
+    assert_eq!(d[0].a, Duration::days(123));
+    assert_eq!(d[0].b, Duration::days(-1));
+    assert_eq!(d[0].e, Duration::seconds(611));
+}
+
 #[test]
 fn test_option() {
     #[derive(Presto, Eq, PartialEq, Debug, Clone)]
@@ -408,6 +540,89 @@ fn test_date_time() {
     assert_eq!(ds[0], A { a, b, c, d });
 }
 
+#[cfg(feature = "time")]
+#[test]
+fn test_time_date_time() {
+    use time::{Date, Month, OffsetDateTime, PrimitiveDateTime, Time};
+
+    #[derive(Presto, PartialEq, Debug, Clone)]
+    struct A {
+        a: Date,
+        b: Time,
+        c: PrimitiveDateTime,
+        d: OffsetDateTime,
+    }
+
+    let (s, v) = read("date_time");
+    let ds = serde_json::from_str::<DataSet<A>>(&s).unwrap();
+    assert_ds(ds.clone(), v);
+    let ds = ds.into_vec();
+    assert_eq!(ds.len(), 1);
+    let a = Date::from_calendar_date(2001, Month::August, 22).unwrap();
+    let b = Time::from_hms_milli(1, 2, 3, 456).unwrap();
+    let c = PrimitiveDateTime::new(a, Time::from_hms_milli(3, 4, 5, 321).unwrap());
+    let d = c.assume_utc();
+    assert_eq!(ds[0], A { a, b, c, d });
+}
+
+#[cfg(feature = "jiff")]
+#[test]
+fn test_jiff_date_time() {
+    use jiff::civil::{date, time, Date, DateTime, Time};
+    use jiff::{Timestamp, Zoned};
+
+    #[derive(Presto, PartialEq, Debug, Clone)]
+    struct A {
+        a: Date,
+        b: Time,
+        c: DateTime,
+        d: Zoned,
+    }
+
+    let (s, v) = read("date_time");
+    let ds = serde_json::from_str::<DataSet<A>>(&s).unwrap();
+    let data = serde_json::to_value(ds.as_slice()[0].value()).unwrap();
+    assert_eq!(data[0], v["data"][0][0]);
+    assert_eq!(data[1], v["data"][0][1]);
+    assert_eq!(data[2], v["data"][0][2]);
+    // a zero offset is read as utc
+    assert_eq!(data[3], "2001-08-22 03:04:05.321 UTC");
+    let ds = ds.into_vec();
+    assert_eq!(ds.len(), 1);
+    assert_eq!(ds[0].a, date(2001, 8, 22));
+    assert_eq!(ds[0].b, time(1, 2, 3, 456_000_000));
+    assert_eq!(ds[0].c, date(2001, 8, 22).at(3, 4, 5, 321_000_000));
+    assert_eq!(ds[0].d.to_string(), "2001-08-22T03:04:05.321+00:00[UTC]");
+    // jiff keeps nanoseconds like chrono, although `Zoned` is read through `ZonedTimestamp`
+    assert_eq!(Zoned::ty(), PrestoTy::TimestampWithTimeZone(Some(9)));
+
+    #[derive(Presto, PartialEq, Debug, Clone)]
+    struct B {
+        a: Zoned,
+        b: Timestamp,
+        c: Zoned,
+        d: ZonedTime,
+        e: Zoned,
+    }
+
+    let (s, v) = read("zoned");
+    let ds = serde_json::from_str::<DataSet<B>>(&s).unwrap();
+    let data = serde_json::to_value(ds.as_slice()[0].value()).unwrap();
+    assert_eq!(data[0], v["data"][0][0]);
+    assert_eq!(data[1], "2024-01-01 02:00:00.000 UTC");
+    assert_eq!(data[2], v["data"][0][2]);
+    assert_eq!(data[4], v["data"][0][4]);
+    let ds = ds.into_vec();
+    assert_eq!(
+        ds[0].a.to_string(),
+        "2024-01-01T10:00:00-05:00[America/New_York]"
+    );
+    assert_eq!(
+        ds[0].b,
+        "2024-01-01T02:00:00Z".parse::<Timestamp>().unwrap()
+    );
+}
+
 #[test]
 fn test_decimal() {
     #[derive(Presto, PartialEq, Debug, Clone)]
@@ -429,97 +644,601 @@ fn test_decimal() {
 }
 
 #[test]
-fn test_complex() {
+fn test_dyn_decimal() {
     #[derive(Presto, PartialEq, Debug, Clone)]
     struct A {
-        a: String,
-        b: i32,
-        c: bool,
-        d: Vec<i32>,
-        e: B,
+        a: DynDecimal,
     }
 
     #[derive(Presto, PartialEq, Debug, Clone)]
     struct B {
-        x: i64,
-        y: f64,
+        a: BigDecimal,
     }
 
-    // test custom type
-    let (s, v) = read("complex");
-    let d = serde_json::from_str::<DataSet<A>>(&s).unwrap();
-    assert_ds(d.clone(), v);
+    let s = "1123412341234123412341234.2222222220";
+    let (data, v) = read("decimal");
 
-    let (t, d) = d.split();
-    let ty = PrestoTy::Row(t);
+    let d = serde_json::from_str::<DataSet<A>>(&data).unwrap();
+    assert_ds(d.clone(), v.clone());
+    let d = d.into_vec();
     assert_eq!(d.len(), 1);
-    assert_eq!(ty, A::ty());
+    assert_eq!(d[0].a.precision(), 38);
+    assert_eq!(d[0].a.scale(), 10);
+    assert_eq!(d[0].a.to_string(), s);
+
+    let d = serde_json::from_str::<DataSet<B>>(&data).unwrap();
+    assert_ds(d.clone(), v.clone());
+    let d = d.into_vec();
+    assert_eq!(d[0].a, BigDecimal::from_str(s).unwrap());
+
+    #[derive(Presto, PartialEq, Debug, Clone)]
+    struct C {
+        a: Option<DynDecimal>,
+    }
+    let d = serde_json::from_str::<DataSet<C>>(&data).unwrap();
+    assert_ds(d, v);
+
+    assert_eq!(
+        A::ty(),
+        PrestoTy::Row(vec![("a".into(), PrestoTy::DynDecimal)])
+    );
+    assert_eq!(PrestoTy::DynDecimal.full_type(), "decimal");
+}
+
+#[cfg(feature = "rust_decimal")]
+#[test]
+fn test_rust_decimal() {
+    #[derive(Presto, PartialEq, Debug, Clone)]
+    struct A {
+        a: rust_decimal::Decimal,
+    }
+
+    // the value in the fixture has more digits than rust_decimal keeps
+    let (data, mut v) = read("decimal");
+    let e = serde_json::from_str::<DataSet<A>>(&data).unwrap_err();
+    assert!(e.to_string().contains("rust_decimal"));
+
+    let s = "1123412341234123412.2222222220";
+    v["data"][0][0] = Value::String(s.into());
+    let d = serde_json::from_value::<DataSet<A>>(v.clone()).unwrap();
+    assert_ds(d.clone(), v.clone());
+    let d = d.into_vec();
+    assert_eq!(d[0].a, rust_decimal::Decimal::from_str_exact(s).unwrap());
+
+    // a scale wider than rust_decimal keeps is fine if the extra digits are zeros
+    v["columns"][0]["type"] = Value::String("decimal(38,30)".into());
+    v["columns"][0]["typeSignature"]["arguments"][1]["value"] = 30.into();
+    v["data"][0][0] = Value::String("0.123000000000000000000000000000".into());
+    let d = serde_json::from_value::<DataSet<A>>(v.clone())
+        .unwrap()
+        .into_vec();
+    assert_eq!(
+        d[0].a,
+        rust_decimal::Decimal::from_str_exact("0.123").unwrap()
+    );
+
+    v["data"][0][0] = Value::String("0.123000000000000000000000000001".into());
+    assert!(serde_json::from_value::<DataSet<A>>(v).is_err());
+}
+
+#[test]
+fn test_coercion() {
+    #[derive(Presto, PartialEq, Debug, Clone)]
+    struct A {
+        a: i64,
+        b: f32,
+        c: String,
+        d: Decimal<10, 2>,
+        e: i64,
+    }
+
+    #[derive(Presto, PartialEq, Debug, Clone)]
+    struct B {
+        a: i8,
+        b: f64,
+        c: String,
+        d: Decimal<10, 2>,
+        e: i32,
+    }
+
+    #[derive(Presto, PartialEq, Debug, Clone)]
+    struct C {
+        a: i64,
+        b: f64,
+        c: FixedChar<4>,
+        d: f64,
+        e: Decimal<12, 2>,
+    }
+
+    let (s, _) = read("coercion");
+
+    let d = serde_json::from_str::<DataSet<A>>(&s).unwrap().into_vec();
+    assert_eq!(d[0].a, 300);
+    assert_eq!(d[0].b, 1.5);
+
+    let e = serde_json::from_str::<DataSet<B>>(&s).unwrap_err();
+    assert!(e.to_string().contains("300 overflows i8"), "{}", e);
+
+    let e = serde_json::from_str::<DataSet<C>>(&s).unwrap_err();
+    assert!(
+        e.to_string()
+            .contains("`c` expected char(4), found varchar"),
+        "{}",
+        e
+    );
+
+    let d = serde_json::from_str::<DataSet<Lenient<C>>>(&s)
+        .unwrap()
+        .into_vec();
+    let c = d[0].clone().into_inner();
+    assert_eq!(c.c.into_string(), "ab  ");
+    assert_eq!(c.d, 12.34);
+    assert_eq!(c.e, Decimal::from_str("7.00").unwrap());
+}
+
+mod string_number {
+    use std::num::ParseIntError;
+
+    pub type Proxy = String;
+
+    pub fn to_presto(v: &u64) -> String {
+        v.to_string()
+    }
+
+    pub fn from_presto(s: String) -> Result<u64, ParseIntError> {
+        s.parse()
+    }
+}
+
+#[test]
+fn test_attributes() {
+    #[derive(Presto, PartialEq, Debug, Clone)]
+    struct A {
+        #[presto(rename = "order-id")]
+        id: i64,
+        #[presto(with = "string_number")]
+        total: u64,
+        #[presto(default)]
+        note: Option<String>,
+        #[presto(skip)]
+        cached: Vec<i32>,
+    }
+
+    #[derive(Presto, PartialEq, Debug, Clone)]
+    struct B {
+        #[presto(rename = "order-id")]
+        id: i64,
+        note: Option<String>,
+    }
+
+    assert_eq!(
+        A::ty(),
+        PrestoTy::Row(vec![
+            ("order-id".into(), PrestoTy::PrestoInt(PrestoInt::I64)),
+            ("total".into(), PrestoTy::Varchar(None)),
+            (
+                "note".into(),
+                PrestoTy::Defaulted(Box::new(PrestoTy::Option(Box::new(PrestoTy::Varchar(
+                    None
+                )))))
+            ),
+        ])
+    );
+
+    let (s, _) = read("attributes");
+    let d = serde_json::from_str::<DataSet<A>>(&s).unwrap().into_vec();
     assert_eq!(
         d[0],
         A {
-            a: "abc".into(),
-            b: 10,
-            c: true,
-            d: vec![1, 2, 3],
-            e: B { x: 1, y: 1.1 }
+            id: 1,
+            total: 42,
+            note: None,
+            cached: vec![],
         }
     );
+
+    let v = serde_json::to_value(DataSet::new(d).unwrap()).unwrap();
+    assert_eq!(v["data"], serde_json::json!([[1, "42", null]]));
+
+    let e = serde_json::from_str::<DataSet<B>>(&s).unwrap_err();
+    assert!(e.to_string().contains("`note` is missing"), "{}", e);
 }
 
 #[test]
-fn test_complex_row() {
-    use PrestoFloat::*;
-    use PrestoInt::*;
+fn test_enum() {
+    #[derive(Presto, PartialEq, Debug, Clone, Copy)]
+    #[presto(rename_all = "SCREAMING_SNAKE_CASE")]
+    enum Status {
+        Active,
+        PendingReview,
+        #[presto(rename = "GONE")]
+        Deleted,
+    }
 
-    let (s, v) = read("complex");
-    let d = serde_json::from_str::<DataSet<Row>>(&s).unwrap();
-    assert_ds(d.clone(), v);
-    let (t, _) = d.split();
+    #[derive(Presto, PartialEq, Debug, Clone, Copy)]
+    #[presto(rename_all = "SCREAMING_SNAKE_CASE")]
+    enum LenientStatus {
+        Active,
+        PendingReview,
+        #[presto(other)]
+        Unknown,
+    }
+
+    #[derive(Presto, PartialEq, Debug, Clone, Copy)]
+    #[presto(repr = "i32")]
+    enum Kind {
+        Small = 1,
+        Large,
+    }
+
+    #[derive(Presto, PartialEq, Debug, Clone, Copy)]
+    #[presto(repr = "i32")]
+    enum LenientKind {
+        Small = 1,
+        Large,
+        #[presto(other)]
+        Unknown = -1,
+    }
+
+    #[derive(Presto, PartialEq, Debug, Clone)]
+    struct A {
+        status: LenientStatus,
+        kind: LenientKind,
+    }
+
+    #[derive(Presto, PartialEq, Debug, Clone)]
+    struct B {
+        status: Status,
+        kind: LenientKind,
+    }
+
+    #[derive(Presto, PartialEq, Debug, Clone)]
+    struct C {
+        status: LenientStatus,
+        kind: Kind,
+    }
+
+    assert_eq!(Status::ty(), PrestoTy::Varchar(None));
+    assert_eq!(Kind::ty(), PrestoTy::PrestoInt(PrestoInt::I32));
+    assert_eq!(Status::PendingReview.value(), "PENDING_REVIEW");
+    assert_eq!(Status::Deleted.value(), "GONE");
+    assert_eq!(Kind::Large.value(), 2);
 
+    let (s, v) = read("enum");
+    let d = serde_json::from_str::<DataSet<A>>(&s).unwrap().into_vec();
     assert_eq!(
-        t,
+        d,
         vec![
-            ("a".into(), PrestoTy::Varchar),
-            ("b".into(), PrestoTy::PrestoInt(I32)),
-            ("c".into(), PrestoTy::Boolean),
-            (
-                "d".into(),
-                PrestoTy::Array(Box::new(PrestoTy::PrestoInt(I32)))
-            ),
-            (
-                "e".into(),
-                PrestoTy::Row(vec![
-                    ("x".into(), PrestoTy::PrestoInt(I64)),
-                    ("y".into(), PrestoTy::PrestoFloat(F64))
-                ])
-            ),
+            A {
+                status: LenientStatus::Active,
+                kind: LenientKind::Small,
+            },
+            A {
+                status: LenientStatus::PendingReview,
+                kind: LenientKind::Large,
+            },
+            A {
+                status: LenientStatus::Unknown,
+                kind: LenientKind::Unknown,
+            },
         ]
     );
+
+    let e = serde_json::from_str::<DataSet<B>>(&s).unwrap_err();
+    assert!(
+        e.to_string().contains(
+            "unknown variant `ARCHIVED`, expected one of `ACTIVE`, `PENDING_REVIEW`, `GONE`"
+        ),
+        "{}",
+        e
+    );
+
+    let e = serde_json::from_str::<DataSet<C>>(&s).unwrap_err();
+    assert!(e.to_string().contains("unknown value 3 of Kind"), "{}", e);
+
+    let d = serde_json::from_str::<DataSet<A>>(&s).unwrap();
+    let mut v = v;
+    v["data"][2] = serde_json::json!(["UNKNOWN", -1]);
+    assert_ds(d, v);
 }
 
 #[test]
-fn test_complex_reorder() {
+fn test_tuple_struct() {
+    #[derive(Presto, PartialEq, Debug, Clone, Copy)]
+    #[presto(transparent)]
+    struct UserId(i64);
+
+    #[derive(Presto, PartialEq, Debug, Clone)]
+    #[presto(transparent)]
+    struct Named {
+        id: i32,
+    }
+
+    #[derive(Presto, PartialEq, Debug, Clone)]
+    struct Point(f64, f64);
+
     #[derive(Presto, PartialEq, Debug, Clone)]
     struct A {
-        d: Vec<i32>, //0
-        c: bool,     //
-        b: i32,      //
-        e: B,
-        a: String, //4
+        id: UserId,
+        point: Point,
     }
 
     #[derive(Presto, PartialEq, Debug, Clone)]
     struct B {
-        y: f64,
-        x: i64,
+        id: Named,
+        point: Point,
     }
 
-    // test custom type
-    let (s, _) = read("complex");
+    assert_eq!(UserId::ty(), PrestoTy::PrestoInt(PrestoInt::I64));
+    assert_eq!(
+        Point::ty(),
+        PrestoTy::Tuple(vec![
+            PrestoTy::PrestoFloat(PrestoFloat::F64),
+            PrestoTy::PrestoFloat(PrestoFloat::F64)
+        ])
+    );
+
+    let (s, v) = read("tuple");
     let d = serde_json::from_str::<DataSet<A>>(&s).unwrap();
+    assert_eq!(
+        d.as_slice(),
+        &[A {
+            id: UserId(7),
+            point: Point(1.5, 2.5),
+        }]
+    );
+    assert_ds(d, v);
 
-    let (t, d) = d.split();
-    let ty = PrestoTy::Row(t);
+    let d = serde_json::from_str::<DataSet<B>>(&s).unwrap().into_vec();
+    assert_eq!(d[0].id, Named { id: 7 });
+}
+
+#[test]
+fn test_flatten() {
+    #[derive(Presto, PartialEq, Debug, Clone)]
+    struct Audit {
+        created_by: String,
+        #[presto(flatten)]
+        update: Update,
+    }
+
+    #[derive(Presto, PartialEq, Debug, Clone)]
+    struct Update {
+        updated_by: String,
+        version: i32,
+    }
+
+    #[derive(Presto, PartialEq, Debug, Clone)]
+    struct A {
+        id: i64,
+        #[presto(flatten)]
+        audit: Audit,
+        name: String,
+    }
+
+    let varchar = || PrestoTy::Varchar(None);
+    assert_eq!(
+        A::ty(),
+        PrestoTy::Row(vec![
+            ("id".into(), PrestoTy::PrestoInt(PrestoInt::I64)),
+            ("created_by".into(), varchar()),
+            ("updated_by".into(), varchar()),
+            ("version".into(), PrestoTy::PrestoInt(PrestoInt::I32)),
+            ("name".into(), varchar()),
+        ])
+    );
+
+    let (s, _) = read("flatten");
+    let d = serde_json::from_str::<DataSet<A>>(&s).unwrap().into_vec();
+    let a = A {
+        id: 1,
+        audit: Audit {
+            created_by: "alice".into(),
+            update: Update {
+                updated_by: "bob".into(),
+                version: 3,
+            },
+        },
+        name: "foo".into(),
+    };
+    assert_eq!(d, vec![a.clone()]);
+
+    let v = serde_json::to_value(a.value()).unwrap();
+    assert_eq!(v, serde_json::json!([1, "alice", "bob", 3, "foo"]));
+
+    #[derive(Presto, PartialEq, Debug, Clone)]
+    struct Created {
+        created_by: String,
+        id: i64,
+    }
+
+    #[derive(Presto, PartialEq, Debug, Clone)]
+    struct B {
+        id: i64,
+        #[presto(flatten)]
+        created: Created,
+    }
+
+    let mut v = serde_json::to_value(DataSet::new(vec![a]).unwrap()).unwrap();
+    v["columns"].as_array_mut().unwrap().truncate(2);
+    v["data"] = serde_json::json!([[1, "alice"]]);
+    let e = serde_json::from_value::<DataSet<B>>(v.clone()).unwrap_err();
+    assert!(e.to_string().contains("`id` is duplicated"), "{}", e);
+
+    // a hand written `RowFields` whose type is not a row
+    #[derive(Presto, PartialEq, Debug, Clone)]
+    #[presto(transparent)]
+    struct Creator(String);
+
+    impl prusto::types::RowFields for Creator {
+        const FIELDS: usize = 1;
+
+        fn serialize_fields<S: serde::ser::SerializeTuple>(
+            &self,
+            s: &mut S,
+        ) -> Result<(), S::Error> {
+            s.serialize_element(&self.0)
+        }
+
+        fn access_field<'a, 'de, A: serde::de::SeqAccess<'de>>(
+            &mut self,
+            _idx: usize,
+            seq: &mut A,
+            ctx: &'a prusto::types::Context<'a>,
+        ) -> Result<(), A::Error> {
+            self.0 = seq
+                .next_element_seed(String::seed(ctx))?
+                .unwrap_or_default();
+            Ok(())
+        }
+    }
+
+    #[derive(Presto, PartialEq, Debug, Clone)]
+    struct C {
+        id: i64,
+        #[presto(flatten)]
+        created_by: Creator,
+    }
+
+    let e = serde_json::from_value::<DataSet<C>>(v).unwrap_err();
+    assert!(
+        e.to_string()
+            .contains("`created_by` has no fields, only rows can be flattened"),
+        "{}",
+        e
+    );
+}
+
+#[test]
+fn test_native_tuple() {
+    let (s, v) = read("row");
+    let d = serde_json::from_str::<DataSet<((i32, i32), i32)>>(&s).unwrap();
+    assert_eq!(d.as_slice(), &[((1, 1), 5)]);
+    assert_ds(d, v);
+
+    let (s, v) = read("tuple");
+    let d = serde_json::from_str::<DataSet<(i64, (f64, f64))>>(&s).unwrap();
+    assert_eq!(d.as_slice(), &[(7, (1.5, 2.5))]);
+    assert_ds(d, v.clone());
+
+    let d = serde_json::from_str::<DataSet<Lenient<(i64, (f64, f64))>>>(&s).unwrap();
+    assert_eq!(d.as_slice(), &[Lenient((7, (1.5, 2.5)))]);
+    assert_ds(d, v);
+
+    let e = serde_json::from_str::<DataSet<(i64, f64)>>(&s).unwrap_err();
+    assert!(
+        e.to_string().contains("`point` expected double, found row"),
+        "{}",
+        e
+    );
+    let e = serde_json::from_str::<DataSet<(i64,)>>(&s).unwrap_err();
+    assert!(
+        e.to_string().contains("expected 1 fields, found 2"),
+        "{}",
+        e
+    );
+
+    let d = DataSet::new(vec![(1i64, "a".to_string())]).unwrap();
+    let (types, _) = d.split();
+    assert_eq!(
+        types,
+        vec![
+            ("_col0".to_string(), PrestoTy::PrestoInt(PrestoInt::I64)),
+            ("_col1".to_string(), PrestoTy::Varchar(None)),
+        ]
+    );
+}
+
+#[test]
+fn test_geometry_row() {
+    let (s, v) = read("geometry");
+    let d = serde_json::from_str::<DataSet<Row>>(&s).unwrap();
+    assert_ds(d.clone(), v);
+
+    let (types, _) = d.split();
+    assert_eq!(types[0].1, PrestoTy::Geometry);
+    assert_eq!(types[1].1, PrestoTy::SphericalGeography);
+    assert_eq!(types[2].1, PrestoTy::BingTile);
+
+    let tile = BingTile::new(3, 5, 3).unwrap();
+    assert_eq!(tile.sql_literal(), "bing_tile(3, 5, 3)");
+    assert!(BingTile::new(8, 5, 3).is_err());
+}
+
+#[cfg(feature = "geo")]
+#[test]
+fn test_geometry() {
+    use geo_types::{line_string, point, Geometry};
+    use prusto::SphericalGeography;
+
+    #[derive(Presto, PartialEq, Debug, Clone)]
+    struct A {
+        a: Geometry<f64>,
+        b: SphericalGeography,
+        c: BingTile,
+    }
+
+    let (s, v) = read("geometry");
+    let d = serde_json::from_str::<DataSet<A>>(&s).unwrap();
+    assert_ds(d.clone(), v);
+
+    let d = d.into_vec();
+    assert_eq!(d[0].a, Geometry::Point(point!(x: 1.0, y: 2.0)));
+    assert_eq!(
+        d[0].b,
+        SphericalGeography(Geometry::LineString(
+            line_string![(x: 0.0, y: 0.0), (x: 1.0, y: 1.0)]
+        ))
+    );
+    assert_eq!(d[0].c, BingTile::new(3, 5, 3).unwrap());
+    assert_eq!(d[0].a.sql_literal(), "ST_GeometryFromText('POINT(1 2)')");
+}
+
+#[test]
+fn test_other() {
+    #[derive(Presto, PartialEq, Debug, Clone)]
+    struct A {
+        a: RawValue,
+        b: Vec<Value>,
+        c: RawValue,
+    }
+
+    let (s, v) = read("other");
+    let d = serde_json::from_str::<DataSet<Row>>(&s).unwrap();
+    assert_ds(d.clone(), v);
+
+    let d = serde_json::from_str::<DataSet<A>>(&s).unwrap().into_vec();
+    assert_eq!(d[0].a.ty(), &PrestoTy::Other("color".into(), vec![]));
+    assert_eq!(d[0].a.as_json(), &Value::from("#ff0000"));
+    assert_eq!(d[0].b, vec![Value::from("#00ff00")]);
+    assert_eq!(d[0].c.ty().full_type(), "vector(3)");
+    assert_eq!(d[0].c.as_json(), &Value::from(vec![1.0, 2.0, 3.0]));
+}
+
+#[test]
+fn test_complex() {
+    #[derive(Presto, PartialEq, Debug, Clone)]
+    struct A {
+        a: String,
+        b: i32,
+        c: bool,
+        d: Vec<i32>,
+        e: B,
+    }
+
+    #[derive(Presto, PartialEq, Debug, Clone)]
+    struct B {
+        x: i64,
+        y: f64,
+    }
+
+    // test custom type
+    let (s, v) = read("complex");
+    let d = serde_json::from_str::<DataSet<A>>(&s).unwrap();
+    assert_ds(d.clone(), v);
+
+    let (t, d) = d.split();
+    let ty = PrestoTy::Row(t);
     assert_eq!(d.len(), 1);
     assert_eq!(ty, A::ty());
     assert_eq!(
@@ -533,3 +1252,897 @@ fn test_complex_reorder() {
         }
     );
 }
+
+#[test]
+fn test_date_time_precision_row() {
+    let (s, v) = read("date_time_precision");
+    let d = serde_json::from_str::<DataSet<Row>>(&s).unwrap();
+    assert_ds(d.clone(), v);
+
+    let (t, _) = d.split();
+    assert_eq!(t[1].1, PrestoTy::Timestamp(Some(12)));
+    assert_eq!(t[2].1, PrestoTy::Time(Some(12)));
+    assert_eq!(t[3].1, PrestoTy::TimestampWithTimeZone(Some(3)));
+}
+
+#[test]
+fn test_complex_row() {
+    use PrestoFloat::*;
+    use PrestoInt::*;
+
+    let (s, v) = read("complex");
+    let d = serde_json::from_str::<DataSet<Row>>(&s).unwrap();
+    assert_ds(d.clone(), v);
+    let (t, _) = d.split();
+
+    assert_eq!(
+        t,
+        vec![
+            ("a".into(), PrestoTy::Varchar(None)),
+            ("b".into(), PrestoTy::PrestoInt(I32)),
+            ("c".into(), PrestoTy::Boolean),
+            (
+                "d".into(),
+                PrestoTy::Array(Box::new(PrestoTy::PrestoInt(I32)))
+            ),
+            (
+                "e".into(),
+                PrestoTy::Row(vec![
+                    ("x".into(), PrestoTy::PrestoInt(I64)),
+                    ("y".into(), PrestoTy::PrestoFloat(F64))
+                ])
+            ),
+        ]
+    );
+}
+
+#[test]
+fn test_row_value() {
+    let (s, _) = read("complex");
+    let d = serde_json::from_str::<DataSet<Row>>(&s).unwrap().into_vec();
+    let row = &d[0];
+    assert_eq!(row.len(), 5);
+    assert_eq!(
+        row.columns()[1],
+        ("b".into(), PrestoTy::PrestoInt(PrestoInt::I32))
+    );
+    assert_eq!(row.values()[0], PrestoValue::Varchar("abc".into()));
+    assert_eq!(row.values()[1], PrestoValue::Integer(10));
+    assert_eq!(
+        row.values()[3],
+        PrestoValue::Array(vec![
+            PrestoValue::Integer(1),
+            PrestoValue::Integer(2),
+            PrestoValue::Integer(3)
+        ])
+    );
+    assert_eq!(row.get::<i64>(1).unwrap(), 10);
+    assert_eq!(row.get_by_name::<Vec<i32>>("d").unwrap(), vec![1, 2, 3]);
+    assert!(row.get::<String>(1).is_err());
+    assert!(row.get::<i64>(5).is_err());
+    assert!(row.get_by_name::<i64>("f").is_err());
+
+    let e = match &row.values()[4] {
+        PrestoValue::Row(e) => e,
+        v => panic!("expected a row, found {:?}", v),
+    };
+    assert_eq!(
+        e.values(),
+        &[PrestoValue::BigInt(1), PrestoValue::Double(1.1)]
+    );
+    assert_eq!(e.get_by_name::<f64>("y").unwrap(), 1.1);
+    assert_eq!(row.get_by_name::<Row>("e").unwrap(), *e);
+
+    let (s, _) = read("date_time");
+    let d = serde_json::from_str::<DataSet<Row>>(&s).unwrap().into_vec();
+    let day = NaiveDate::from_ymd_opt(2001, 8, 22).unwrap();
+    assert_eq!(d[0].values()[0], PrestoValue::Date(day));
+    assert_eq!(d[0].get_by_name::<NaiveDate>("a").unwrap(), day);
+    assert_eq!(
+        d[0].get_by_name::<NaiveDateTime>("c").unwrap(),
+        day.and_hms_milli_opt(3, 4, 5, 321).unwrap()
+    );
+    assert!(matches!(
+        d[0].values()[3],
+        PrestoValue::TimestampWithTimeZone(_)
+    ));
+
+    let (s, _) = read("decimal");
+    let d = serde_json::from_str::<DataSet<Row>>(&s).unwrap().into_vec();
+    let a = d[0].get::<DynDecimal>(0).unwrap();
+    assert_eq!(d[0].values()[0], PrestoValue::Decimal(a));
+
+    let (s, _) = read("map");
+    let d = serde_json::from_str::<DataSet<Row>>(&s).unwrap().into_vec();
+    assert_eq!(
+        d[0].values()[1],
+        PrestoValue::Map(vec![
+            (PrestoValue::Integer(1), PrestoValue::Integer(1)),
+            (PrestoValue::Integer(2), PrestoValue::Integer(2)),
+        ])
+    );
+    assert_eq!(
+        d[0].get::<BTreeMap<i32, i32>>(1).unwrap(),
+        btreemap! {1 => 1, 2 => 2}
+    );
+}
+
+#[test]
+fn test_data_set_columns() {
+    let (s, _) = read("complex");
+    let d = serde_json::from_str::<DataSet<Row>>(&s).unwrap();
+    assert_eq!(d.columns().len(), 5);
+    assert_eq!(d.columns()[2], ("c".into(), PrestoTy::Boolean));
+    assert_eq!(d.column_index("b"), Some(1));
+    assert_eq!(d.column_index("B"), Some(1));
+    assert_eq!(d.column_index("f"), None);
+    assert_eq!(d.column::<String>("a").unwrap(), vec!["abc".to_string()]);
+    assert_eq!(d.column::<i64>("B").unwrap(), vec![10]);
+    assert!(d.column::<i64>("a").is_err());
+    assert!(d.column::<i64>("f").is_err());
+
+    let p = d.project(&["d", "A"]).unwrap();
+    assert_eq!(
+        p.columns(),
+        &[
+            (
+                "d".to_string(),
+                PrestoTy::Array(Box::new(PrestoTy::PrestoInt(PrestoInt::I32)))
+            ),
+            ("a".to_string(), PrestoTy::Varchar(None)),
+        ]
+    );
+    let rows = p.as_slice();
+    assert_eq!(rows[0].columns(), p.columns());
+    assert_eq!(rows[0].get::<Vec<i32>>(0).unwrap(), vec![1, 2, 3]);
+    assert_eq!(rows[0].get_by_name::<String>("a").unwrap(), "abc");
+    assert_eq!(
+        serde_json::to_value(&p).unwrap()["data"],
+        serde_json::json!([[[1, 2, 3], "abc"]])
+    );
+    assert!(d.project(&["a", "f"]).is_err());
+}
+
+#[test]
+fn test_format() {
+    use prusto::format::*;
+
+    fn write(w: &mut impl RowWriter, pages: &[&DataSet<Row>]) {
+        for d in pages {
+            w.write(d).unwrap();
+        }
+        w.finish().unwrap();
+    }
+
+    let (s, _) = read("complex");
+    let complex = serde_json::from_str::<DataSet<Row>>(&s).unwrap();
+    let (s, _) = read("option");
+    let option = serde_json::from_str::<DataSet<Row>>(&s).unwrap();
+
+    let mut w = CsvWriter::new(vec![]).header(true);
+    write(&mut w, &[&complex, &complex]);
+    assert_eq!(
+        String::from_utf8(w.into_inner()).unwrap(),
+        "a,b,c,d,e\n\
+         abc,10,true,\"[1, 2, 3]\",\"{x=1, y=1.1}\"\n\
+         abc,10,true,\"[1, 2, 3]\",\"{x=1, y=1.1}\"\n"
+    );
+
+    let mut w = CsvWriter::new(vec![]).quote(Quote::Always).delimiter(';');
+    write(&mut w, &[&option]);
+    assert_eq!(
+        String::from_utf8(w.into_inner()).unwrap(),
+        "\"a\";\n\"b\";\"Some(b)\"\n\"c\";\n"
+    );
+
+    let mut w = CsvWriter::new(vec![]).quote(Quote::Never);
+    write(&mut w, &[&complex]);
+    assert_eq!(
+        String::from_utf8(w.into_inner()).unwrap(),
+        "abc,10,true,[1, 2, 3],{x=1, y=1.1}\n"
+    );
+
+    let mut w = TsvWriter::new(vec![]).header(true);
+    write(&mut w, &[&option]);
+    assert_eq!(
+        String::from_utf8(w.into_inner()).unwrap(),
+        "a\tb\na\t\nb\tSome(b)\nc\t\n"
+    );
+
+    let mut w = JsonLinesWriter::new(vec![]);
+    write(&mut w, &[&complex, &option]);
+    assert_eq!(
+        String::from_utf8(w.into_inner()).unwrap(),
+        "{\"a\":\"abc\",\"b\":10,\"c\":true,\"d\":[1,2,3],\"e\":[1,1.1]}\n\
+         {\"a\":\"a\",\"b\":null}\n\
+         {\"a\":\"b\",\"b\":\"Some(b)\"}\n\
+         {\"a\":\"c\",\"b\":null}\n"
+    );
+
+    let mut w = AlignedWriter::new(vec![]);
+    write(&mut w, &[&complex, &complex]);
+    assert_eq!(
+        String::from_utf8(w.into_inner()).unwrap(),
+        "  a  | b  |  c   |     d     |      e\n\
+         -----+----+------+-----------+--------------\n \
+         abc | 10 | true | [1, 2, 3] | {x=1, y=1.1}\n \
+         abc | 10 | true | [1, 2, 3] | {x=1, y=1.1}\n\
+         (2 rows)\n"
+    );
+
+    // the widths fit the rows of every page, not only the first one
+    let (s, _) = read("complex");
+    let s = s.replace("\"abc\"", "\"abcdefg\"");
+    let wide = serde_json::from_str::<DataSet<Row>>(&s).unwrap();
+    let mut w = AlignedWriter::new(vec![]);
+    write(&mut w, &[&complex, &wide]);
+    assert_eq!(
+        String::from_utf8(w.into_inner()).unwrap(),
+        "    a    | b  |  c   |     d     |      e\n\
+         ---------+----+------+-----------+--------------\n \
+         abc     | 10 | true | [1, 2, 3] | {x=1, y=1.1}\n \
+         abcdefg | 10 | true | [1, 2, 3] | {x=1, y=1.1}\n\
+         (2 rows)\n"
+    );
+
+    let mut w = VerticalWriter::new(vec![]);
+    write(&mut w, &[&option]);
+    let s = String::from_utf8(w.into_inner()).unwrap();
+    assert_eq!(
+        s.lines().take(6).collect::<Vec<_>>(),
+        vec![
+            "-[ RECORD 1 ]+-----",
+            "a | a",
+            "b | NULL",
+            "-[ RECORD 2 ]+--------",
+            "a | b",
+            "b | Some(b)",
+        ]
+    );
+
+    let mut w = MarkdownWriter::new(vec![]);
+    write(&mut w, &[&complex]);
+    assert_eq!(
+        String::from_utf8(w.into_inner()).unwrap(),
+        "| a | b | c | d | e |\n\
+         | --- | ---: | --- | --- | --- |\n\
+         | abc | 10 | true | [1, 2, 3] | {x=1, y=1.1} |\n"
+    );
+
+    let mut buf = vec![];
+    write(&mut Format::Aligned.writer(&mut buf), &[]);
+    assert_eq!(String::from_utf8(buf).unwrap(), "(0 rows)\n");
+}
+
+#[test]
+fn test_serde() {
+    use prusto::Serde;
+    use serde::Deserialize;
+
+    #[derive(Deserialize, Debug, PartialEq)]
+    struct Point {
+        x: i64,
+        y: f64,
+    }
+
+    #[derive(Deserialize, Debug, PartialEq)]
+    struct Complex {
+        #[serde(rename = "a")]
+        name: String,
+        b: i32,
+        d: Vec<i32>,
+        e: Point,
+        #[serde(default)]
+        tags: Vec<String>,
+        #[serde(flatten)]
+        rest: HashMap<String, Value>,
+    }
+
+    let (s, _) = read("complex");
+    let d = serde_json::from_str::<DataSet<Serde<Complex>>>(&s).unwrap();
+    assert_eq!(d.columns().len(), 5);
+    let expected = Complex {
+        name: "abc".to_string(),
+        b: 10,
+        d: vec![1, 2, 3],
+        e: Point { x: 1, y: 1.1 },
+        tags: vec![],
+        rest: hashmap! {"c".to_string() => Value::Bool(true)},
+    };
+    assert_eq!(*d.as_slice()[0], expected);
+    assert!(d.as_slice()[0].row().get_by_name::<bool>("c").unwrap());
+
+    // written back as the row it is decoded from
+    let s = serde_json::to_string(&d).unwrap();
+    let d2 = serde_json::from_str::<DataSet<Serde<Complex>>>(&s).unwrap();
+    assert_eq!(d.as_slice(), d2.as_slice());
+
+    #[derive(Deserialize, Debug, PartialEq)]
+    struct Amount {
+        #[serde(rename = "a")]
+        amount: String,
+    }
+    let (s, _) = read("decimal");
+    let d = serde_json::from_str::<DataSet<Serde<Amount>>>(&s).unwrap();
+    assert_eq!(
+        d.into_vec()[0].amount,
+        "1123412341234123412341234.2222222220"
+    );
+
+    #[derive(Deserialize, Debug, PartialEq)]
+    struct Missing {
+        z: i32,
+    }
+    let (s, v) = read("row");
+    assert!(serde_json::from_str::<DataSet<Serde<Missing>>>(&s).is_err());
+
+    // nested rows can be decoded with serde as well
+    #[derive(Deserialize, Debug, PartialEq)]
+    struct Pair {
+        x: i32,
+        y: i32,
+    }
+
+    #[derive(Presto, Debug, PartialEq)]
+    struct Outer {
+        a: Serde<Pair>,
+        b: i32,
+    }
+    let d = serde_json::from_str::<DataSet<Outer>>(&s).unwrap();
+    assert_eq!(*d.as_slice()[0].a, Pair { x: 1, y: 1 });
+    assert_eq!(d.as_slice()[0].b, 5);
+    assert_ds(d, v);
+}
+
+#[cfg(feature = "arrow")]
+#[test]
+fn test_record_batch() {
+    use arrow_array::cast::AsArray;
+    use arrow_array::types::*;
+    use arrow_array::Array;
+    use arrow_schema::{DataType, Field, Fields, TimeUnit};
+    use std::sync::Arc;
+
+    let (s, _) = read("complex");
+    let d = serde_json::from_str::<DataSet<Row>>(&s).unwrap();
+    let batch = d.to_record_batch().unwrap();
+    assert_eq!(batch.num_rows(), 1);
+    assert_eq!(
+        batch.schema().field(0),
+        &Field::new("a", DataType::Utf8, true)
+    );
+    assert_eq!(
+        batch.schema().field(3).data_type(),
+        &DataType::List(Arc::new(Field::new_list_field(DataType::Int32, true)))
+    );
+    assert_eq!(
+        batch.schema().field(4).data_type(),
+        &DataType::Struct(Fields::from(vec![
+            Field::new("x", DataType::Int64, true),
+            Field::new("y", DataType::Float64, true),
+        ]))
+    );
+    assert_eq!(batch.column(0).as_string::<i32>().value(0), "abc");
+    assert_eq!(batch.column(1).as_primitive::<Int32Type>().value(0), 10);
+    assert!(batch.column(2).as_boolean().value(0));
+    let d = batch.column(3).as_list::<i32>().value(0);
+    assert_eq!(d.as_primitive::<Int32Type>().values(), &[1, 2, 3]);
+    let e = batch.column(4).as_struct();
+    assert_eq!(e.column(0).as_primitive::<Int64Type>().value(0), 1);
+    assert_eq!(e.column(1).as_primitive::<Float64Type>().value(0), 1.1);
+
+    let (s, _) = read("option");
+    let d = serde_json::from_str::<DataSet<Row>>(&s).unwrap();
+    let batch = d.to_record_batch().unwrap();
+    let b = batch.column(1).as_string::<i32>();
+    assert_eq!(b.null_count(), 2);
+    assert!(b.is_null(0));
+    assert_eq!(b.value(1), "Some(b)");
+
+    let (s, _) = read("date_time");
+    let d = serde_json::from_str::<DataSet<Row>>(&s).unwrap();
+    let batch = d.to_record_batch().unwrap();
+    let schema = batch.schema();
+    assert_eq!(schema.field(0).data_type(), &DataType::Date32);
+    assert_eq!(
+        schema.field(1).data_type(),
+        &DataType::Time32(TimeUnit::Millisecond)
+    );
+    assert_eq!(
+        schema.field(3).data_type(),
+        &DataType::Timestamp(TimeUnit::Millisecond, Some("UTC".into()))
+    );
+    let day = NaiveDate::from_ymd_opt(2001, 8, 22).unwrap();
+    let epoch = NaiveDate::from_ymd_opt(1970, 1, 1).unwrap();
+    assert_eq!(
+        batch.column(0).as_primitive::<Date32Type>().value(0),
+        (day - epoch).num_days() as i32
+    );
+    assert_eq!(
+        batch
+            .column(1)
+            .as_primitive::<Time32MillisecondType>()
+            .value(0),
+        3_723_456
+    );
+    let ts = day.and_hms_milli_opt(3, 4, 5, 321).unwrap().and_utc();
+    assert_eq!(
+        batch
+            .column(2)
+            .as_primitive::<TimestampMillisecondType>()
+            .value(0),
+        ts.timestamp_millis()
+    );
+    assert_eq!(
+        batch
+            .column(3)
+            .as_primitive::<TimestampMillisecondType>()
+            .value(0),
+        ts.timestamp_millis()
+    );
+
+    let (s, _) = read("date_time_precision");
+    let d = serde_json::from_str::<DataSet<Row>>(&s).unwrap();
+    let schema = d.arrow_schema();
+    assert_eq!(
+        schema.field(0).data_type(),
+        &DataType::Timestamp(TimeUnit::Nanosecond, None)
+    );
+    assert_eq!(
+        schema.field(4).data_type(),
+        &DataType::Timestamp(TimeUnit::Second, None)
+    );
+    assert!(d.to_record_batch().is_ok());
+
+    let (s, _) = read("map");
+    let d = serde_json::from_str::<DataSet<Row>>(&s).unwrap();
+    let batch = d.to_record_batch().unwrap();
+    let b = batch.column(1).as_map();
+    assert_eq!(b.value_length(0), 2);
+    assert_eq!(b.keys().as_primitive::<Int32Type>().values(), &[1, 2]);
+    assert_eq!(b.values().as_primitive::<Int32Type>().values(), &[1, 2]);
+
+    let (s, _) = read("decimal");
+    let d = serde_json::from_str::<DataSet<Row>>(&s).unwrap();
+    let batch = d.to_record_batch().unwrap();
+    let a = batch.column(0).as_primitive::<Decimal128Type>();
+    assert_eq!(a.data_type(), &DataType::Decimal128(38, 10));
+    assert_eq!(a.value_as_string(0), "1123412341234123412341234.2222222220");
+}
+
+#[cfg(feature = "polars")]
+#[test]
+fn test_dataframe() {
+    use polars::prelude::*;
+
+    let (s, _) = read("complex");
+    let d = serde_json::from_str::<DataSet<Row>>(&s).unwrap();
+    let df = d.to_dataframe().unwrap();
+    assert_eq!(df.shape(), (1, 5));
+    assert_eq!(
+        df.dtypes(),
+        vec![
+            DataType::String,
+            DataType::Int32,
+            DataType::Boolean,
+            DataType::List(Box::new(DataType::Int32)),
+            DataType::Struct(vec![
+                Field::new("x".into(), DataType::Int64),
+                Field::new("y".into(), DataType::Float64),
+            ]),
+        ]
+    );
+    assert_eq!(df.column("a").unwrap().str().unwrap().get(0), Some("abc"));
+    assert_eq!(df.column("b").unwrap().i32().unwrap().get(0), Some(10));
+    let d = df
+        .column("d")
+        .unwrap()
+        .list()
+        .unwrap()
+        .get_as_series(0)
+        .unwrap();
+    assert_eq!(d.i32().unwrap().to_vec(), vec![Some(1), Some(2), Some(3)]);
+    let e = df
+        .column("e")
+        .unwrap()
+        .struct_()
+        .unwrap()
+        .fields_as_series();
+    assert_eq!(e[0].i64().unwrap().get(0), Some(1));
+    assert_eq!(e[1].f64().unwrap().get(0), Some(1.1));
+
+    let (s, _) = read("option");
+    let d = serde_json::from_str::<DataSet<Row>>(&s).unwrap();
+    let df = d.to_dataframe().unwrap();
+    let b = df.get_columns()[1].str().unwrap();
+    assert_eq!(b.null_count(), 2);
+    assert_eq!(b.get(0), None);
+    assert_eq!(b.get(1), Some("Some(b)"));
+
+    let (s, _) = read("date_time");
+    let d = serde_json::from_str::<DataSet<Row>>(&s).unwrap();
+    let df = d.to_dataframe().unwrap();
+    assert_eq!(df.dtypes()[0], DataType::Date);
+    assert_eq!(df.dtypes()[1], DataType::Time);
+    assert_eq!(
+        df.dtypes()[3],
+        DataType::Datetime(TimeUnit::Milliseconds, Some(TimeZone::UTC))
+    );
+    let day = NaiveDate::from_ymd_opt(2001, 8, 22).unwrap();
+    let ts = day.and_hms_milli_opt(3, 4, 5, 321).unwrap().and_utc();
+    let columns = df.get_columns();
+    assert_eq!(
+        columns[1]
+            .as_materialized_series()
+            .time()
+            .unwrap()
+            .phys
+            .get(0),
+        Some(3_723_456_000_000)
+    );
+    assert_eq!(
+        columns[2].datetime().unwrap().phys.get(0),
+        Some(ts.timestamp_millis())
+    );
+    assert_eq!(
+        columns[3].datetime().unwrap().phys.get(0),
+        Some(ts.timestamp_millis())
+    );
+
+    let (s, _) = read("map");
+    let d = serde_json::from_str::<DataSet<Row>>(&s).unwrap();
+    let df = d.to_dataframe().unwrap();
+    let entries = df.get_columns()[1]
+        .list()
+        .unwrap()
+        .get_as_series(0)
+        .unwrap();
+    let entries = entries.struct_().unwrap().fields_as_series();
+    assert_eq!(entries[0].i32().unwrap().to_vec(), vec![Some(1), Some(2)]);
+    assert_eq!(entries[1].i32().unwrap().to_vec(), vec![Some(1), Some(2)]);
+
+    let (s, _) = read("decimal");
+    let d = serde_json::from_str::<DataSet<Row>>(&s).unwrap();
+    let df = d.to_dataframe().unwrap();
+    assert_eq!(df.dtypes()[0], DataType::Decimal(Some(38), Some(10)));
+    assert_eq!(
+        df.get_columns()[0].get(0).unwrap().to_string(),
+        "1123412341234123412341234.2222222220"
+    );
+}
+
+#[cfg(feature = "parquet")]
+#[test]
+fn test_parquet() {
+    use arrow_array::cast::AsArray;
+    use arrow_array::types::*;
+    use arrow_array::Array;
+    use arrow_schema::{DataType, TimeUnit};
+    use parquet::arrow::arrow_reader::ParquetRecordBatchReaderBuilder;
+    use parquet::basic::{LogicalType, Type as PhysicalType};
+    use parquet::file::properties::WriterProperties;
+    use prusto::format::RowWriter;
+    use prusto::ParquetWriter;
+
+    fn write(pages: &[&DataSet<Row>], props: WriterProperties) -> Bytes {
+        let mut w = ParquetWriter::new(vec![]).properties(props);
+        for d in pages {
+            w.write(d).unwrap();
+        }
+        w.finish().unwrap();
+        Bytes::from(w.into_inner().unwrap())
+    }
+
+    let (s, _) = read("option");
+    let d = serde_json::from_str::<DataSet<Row>>(&s).unwrap();
+    let props = WriterProperties::builder()
+        .set_max_row_group_size(2)
+        .build();
+    let reader = ParquetRecordBatchReaderBuilder::try_new(write(&[&d, &d], props)).unwrap();
+    assert_eq!(reader.metadata().num_row_groups(), 3);
+    assert_eq!(reader.metadata().file_metadata().num_rows(), 6);
+    let batches = reader
+        .build()
+        .unwrap()
+        .collect::<Result<Vec<_>, _>>()
+        .unwrap();
+    let b = batches[0].column(1).as_string::<i32>();
+    assert!(b.is_null(0));
+    assert_eq!(b.value(1), "Some(b)");
+
+    let (s, _) = read("complex");
+    let d = serde_json::from_str::<DataSet<Row>>(&s).unwrap();
+    let bytes = write(&[&d], WriterProperties::default());
+    let reader = ParquetRecordBatchReaderBuilder::try_new(bytes).unwrap();
+    assert_eq!(reader.schema().as_ref(), &d.arrow_schema());
+    let batches = reader
+        .build()
+        .unwrap()
+        .collect::<Result<Vec<_>, _>>()
+        .unwrap();
+    assert_eq!(batches[0], d.to_record_batch().unwrap());
+
+    let (s, _) = read("map");
+    let d = serde_json::from_str::<DataSet<Row>>(&s).unwrap();
+    let reader =
+        ParquetRecordBatchReaderBuilder::try_new(write(&[&d], Default::default())).unwrap();
+    let batches = reader
+        .build()
+        .unwrap()
+        .collect::<Result<Vec<_>, _>>()
+        .unwrap();
+    let b = batches[0].column(1).as_map();
+    assert_eq!(b.keys().as_primitive::<Int32Type>().values(), &[1, 2]);
+    assert_eq!(b.values().as_primitive::<Int32Type>().values(), &[1, 2]);
+
+    let (s, _) = read("decimal");
+    let d = serde_json::from_str::<DataSet<Row>>(&s).unwrap();
+    let schema = d.parquet_schema().unwrap();
+    assert_eq!(
+        schema.column(0).logical_type(),
+        Some(LogicalType::Decimal {
+            scale: 10,
+            precision: 38
+        })
+    );
+    let reader =
+        ParquetRecordBatchReaderBuilder::try_new(write(&[&d], Default::default())).unwrap();
+    let batches = reader
+        .build()
+        .unwrap()
+        .collect::<Result<Vec<_>, _>>()
+        .unwrap();
+    let a = batches[0].column(0).as_primitive::<Decimal128Type>();
+    assert_eq!(a.value_as_string(0), "1123412341234123412341234.2222222220");
+
+    let (s, _) = read("date_time_precision");
+    let d = serde_json::from_str::<DataSet<Row>>(&s).unwrap();
+    let reader =
+        ParquetRecordBatchReaderBuilder::try_new(write(&[&d], Default::default())).unwrap();
+    let schema = reader.schema().clone();
+    assert_eq!(
+        schema.field(0).data_type(),
+        &DataType::Timestamp(TimeUnit::Nanosecond, None)
+    );
+    assert_eq!(
+        schema.field(3).data_type(),
+        &DataType::Timestamp(TimeUnit::Millisecond, Some("UTC".into()))
+    );
+    assert_eq!(
+        schema.field(4).data_type(),
+        &DataType::Timestamp(TimeUnit::Millisecond, None)
+    );
+    let batches = reader
+        .build()
+        .unwrap()
+        .collect::<Result<Vec<_>, _>>()
+        .unwrap();
+    let ts = NaiveDate::from_ymd_opt(2001, 8, 22)
+        .unwrap()
+        .and_hms_opt(3, 4, 5)
+        .unwrap()
+        .and_utc();
+    assert_eq!(
+        batches[0]
+            .column(4)
+            .as_primitive::<TimestampMillisecondType>()
+            .value(0),
+        ts.timestamp_millis()
+    );
+
+    let (s, _) = read("interval_day_to_second");
+    let d = serde_json::from_str::<DataSet<Row>>(&s).unwrap();
+    let schema = d.parquet_schema().unwrap();
+    assert_eq!(schema.column(0).physical_type(), PhysicalType::INT64);
+    let reader =
+        ParquetRecordBatchReaderBuilder::try_new(write(&[&d], Default::default())).unwrap();
+    let batches = reader
+        .build()
+        .unwrap()
+        .collect::<Result<Vec<_>, _>>()
+        .unwrap();
+    assert_eq!(
+        batches[0].column(2).as_primitive::<Int64Type>().value(0),
+        13 * 3600 * 1000
+    );
+
+    let (s, _) = read("interval_year_to_month");
+    let d = serde_json::from_str::<DataSet<Row>>(&s).unwrap();
+    let reader =
+        ParquetRecordBatchReaderBuilder::try_new(write(&[&d], Default::default())).unwrap();
+    let batches = reader
+        .build()
+        .unwrap()
+        .collect::<Result<Vec<_>, _>>()
+        .unwrap();
+    assert_eq!(
+        batches[0].column(0).as_primitive::<Int32Type>().value(0),
+        -112
+    );
+
+    let mut w = ParquetWriter::new(vec![]);
+    assert!(w.finish().is_err());
+}
+
+#[test]
+fn test_complex_reorder() {
+    #[derive(Presto, PartialEq, Debug, Clone)]
+    struct A {
+        d: Vec<i32>, //0
+        c: bool,     //
+        b: i32,      //
+        e: B,
+        a: String, //4
+    }
+
+    #[derive(Presto, PartialEq, Debug, Clone)]
+    struct B {
+        y: f64,
+        x: i64,
+    }
+
+    // test custom type
+    let (s, _) = read("complex");
+    let d = serde_json::from_str::<DataSet<A>>(&s).unwrap();
+
+    let (t, d) = d.split();
+    let ty = PrestoTy::Row(t);
+    assert_eq!(d.len(), 1);
+    assert_eq!(ty, A::ty());
+    assert_eq!(
+        d[0],
+        A {
+            a: "abc".into(),
+            b: 10,
+            c: true,
+            d: vec![1, 2, 3],
+            e: B { x: 1, y: 1.1 }
+        }
+    );
+}
+
+#[test]
+fn test_varbinary() {
+    #[derive(Presto, Eq, PartialEq, Debug, Clone)]
+    struct A {
+        a: Binary,
+        b: Vec<Bytes>,
+        c: HashMap<Binary, i32>,
+        d: B,
+    }
+
+    #[derive(Presto, Eq, PartialEq, Debug, Clone)]
+    struct B {
+        x: Bytes,
+    }
+
+    let (s, v) = read("varbinary");
+    let d = serde_json::from_str::<DataSet<A>>(&s).unwrap();
+    assert_ds(d.clone(), v);
+
+    let d = d.into_vec();
+    assert_eq!(d.len(), 1);
+    assert_eq!(
+        d[0],
+        A {
+            a: Binary::new(vec![0, 1, 2, 3]),
+            b: vec![Bytes::from_static(b"hello"), Bytes::new()],
+            c: hashmap![Binary::new(vec![255]) => 1],
+            d: B {
+                x: Bytes::from_static(b"world"),
+            },
+        }
+    );
+}
+
+#[test]
+fn test_sketch() {
+    #[derive(Presto, Eq, PartialEq, Debug, Clone)]
+    struct A {
+        a: HyperLogLog,
+        b: P4HyperLogLog,
+        c: QDigest<i64>,
+        d: TDigest,
+        e: SetDigest,
+    }
+
+    let (s, v) = read("sketch");
+    let d = serde_json::from_str::<DataSet<A>>(&s).unwrap();
+    assert_ds(d.clone(), v);
+
+    let d = d.into_vec();
+    assert_eq!(d.len(), 1);
+    assert_eq!(
+        d[0].a.as_bytes(),
+        &[0x02, 0x0c, 0x01, 0x00, 0x80, 0x43, 0x47, 0x1d]
+    );
+    assert_eq!(
+        d[0].a.sql_literal(),
+        "CAST(X'020C01008043471D' AS HyperLogLog)"
+    );
+    assert!(d[0].c.sql_literal().ends_with(" AS qdigest(bigint))"));
+}
+
+#[test]
+fn test_date_time_precision() {
+    #[derive(Presto, PartialEq, Debug, Clone)]
+    struct A {
+        a: NaiveDateTime,
+        b: PreciseTimestamp,
+        c: PreciseTime,
+        d: DateTime<FixedOffset>,
+        e: PreciseTimestamp,
+    }
+
+    let (s, mut v) = read("date_time_precision");
+    let ds = serde_json::from_str::<DataSet<A>>(&s).unwrap();
+    assert_ds(ds.clone(), v.clone());
+    let data = serde_json::to_value(ds.as_slice()[0].value()).unwrap();
+    assert_eq!(data, v["data"][0]);
+
+    let ds = ds.into_vec();
+    assert_eq!(ds.len(), 1);
+    assert_eq!(
+        ds[0].a,
+        NaiveDate::from_ymd_opt(2001, 8, 22)
+            .unwrap()
+            .and_hms_nano_opt(3, 4, 5, 123456789)
+            .unwrap()
+    );
+    assert_eq!(ds[0].b.picoseconds(), 123456789012);
+    assert_eq!(ds[0].b.precision(), 12);
+    assert_eq!(ds[0].c.picoseconds(), 123456789012);
+    assert_eq!(ds[0].e.precision(), 0);
+
+    // chrono keeps at most nanoseconds, wider columns need `Lenient`
+    v["columns"][0]["type"] = Value::String("timestamp(12)".into());
+    v["columns"][0]["typeSignature"]["arguments"][0]["value"] = 12.into();
+    v["data"][0][0] = Value::String("2001-08-22 03:04:05.123456789012".into());
+    let e = serde_json::from_value::<DataSet<A>>(v.clone()).unwrap_err();
+    assert!(
+        e.to_string()
+            .contains("`a` expected timestamp(9), found timestamp(12)"),
+        "{}",
+        e
+    );
+
+    let ds = serde_json::from_value::<DataSet<Lenient<A>>>(v).unwrap();
+    assert_eq!(ds.as_slice()[0].a.nanosecond(), 123456789);
+}
+
+#[test]
+fn test_zoned() {
+    #[derive(Presto, PartialEq, Debug, Clone)]
+    struct A {
+        a: DateTime<Tz>,
+        b: DateTime<Utc>,
+        c: ZonedTimestamp,
+        d: ZonedTime,
+        e: DateTime<FixedOffset>,
+    }
+
+    let (s, v) = read("zoned");
+    let ds = serde_json::from_str::<DataSet<A>>(&s).unwrap();
+    let data = serde_json::to_value(ds.as_slice()[0].value()).unwrap();
+    assert_eq!(data[0], v["data"][0][0]);
+    assert_eq!(data[1], "2024-01-01 02:00:00.000 UTC");
+    assert_eq!(data[2], v["data"][0][2]);
+    assert_eq!(data[3], v["data"][0][3]);
+    assert_eq!(data[4], "2024-07-01 10:00:00.000 -04:00");
+
+    let ds = ds.into_vec();
+    assert_eq!(ds.len(), 1);
+    assert_eq!(
+        ds[0].a,
+        Tz::America__New_York
+            .with_ymd_and_hms(2024, 1, 1, 10, 0, 0)
+            .unwrap()
+    );
+    assert_eq!(ds[0].b, Utc.with_ymd_and_hms(2024, 1, 1, 2, 0, 0).unwrap());
+    assert_eq!(ds[0].c.zone(), &Zone::Named(Tz::Europe__Berlin));
+    assert_eq!(ds[0].c.local().precision(), 6);
+    assert_eq!(ds[0].d.offset().local_minus_utc(), 8 * 3600);
+
+    let d = serde_json::from_str::<DataSet<Row>>(&s).unwrap();
+    assert_ds(d, v);
+}
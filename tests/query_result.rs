@@ -1,9 +1,12 @@
 #![allow(incomplete_features)]
 
+use std::borrow::Cow;
 use std::fs::File;
 use std::io::Read;
 
-use prusto::{Presto, QueryResult, Row};
+use bytes::Bytes;
+use prusto::{Page, Presto, PrestoBorrow, QueryResult, Row};
+use serde_json::value::RawValue;
 
 fn read(name: &str) -> String {
     let p = "tests/data/models/".to_string() + name;
@@ -92,3 +95,76 @@ fn test_empty() {
     assert!(d.data_set.unwrap().is_empty());
     assert!(d.error.is_none());
 }
+
+#[derive(PrestoBorrow, Debug)]
+struct C<'a> {
+    a: &'a str,
+    b: i32,
+    c: bool,
+    d: Vec<i32>,
+    #[presto(rename = "e")]
+    point: &'a RawValue,
+    f: Option<Cow<'a, str>>,
+}
+
+#[derive(PrestoBorrow, Debug)]
+struct D<'a> {
+    a: Cow<'a, str>,
+    b: i32,
+    c: bool,
+    d: Vec<i32>,
+    e: B,
+    f: Option<&'a str>,
+}
+
+#[test]
+fn test_page() {
+    let s = read("query_result_finished");
+    let page = Page::from_bytes(Bytes::from(s)).unwrap();
+    assert!(page.next_uri.is_none());
+    assert!(page.error.is_none());
+    assert_eq!(page.columns.as_ref().unwrap().len(), 6);
+
+    let rows = page.rows::<C>().unwrap();
+    assert_eq!(rows.len(), 1);
+    let row = &rows[0];
+    assert_eq!(row.a, "abc");
+    assert!(page.body().as_ptr_range().contains(&row.a.as_ptr()));
+    assert_eq!(row.b, 10);
+    assert!(row.c);
+    assert_eq!(row.d, vec![1, 2, 3]);
+    let point: serde_json::Value = serde_json::from_str(row.point.get()).unwrap();
+    assert_eq!(point, serde_json::json!([1, 1.1]));
+    assert_eq!(row.f, None);
+
+    let rows = page.rows::<D>().unwrap();
+    assert_eq!(
+        (rows[0].b, rows[0].c, &rows[0].d, rows[0].f),
+        (10, true, &vec![1, 2, 3], None)
+    );
+    assert_eq!(rows[0].e, B { x: 1, y: 1.1 });
+    assert!(matches!(rows[0].a, Cow::Borrowed("abc")));
+}
+
+#[test]
+fn test_page_escaped() {
+    let s = read("query_result_finished").replace("\"abc\"", "\"a\\\"c\"");
+    let page = Page::from_bytes(Bytes::from(s)).unwrap();
+
+    let e = page.rows::<C>().unwrap_err();
+    assert!(
+        e.to_string().contains("expected a borrowed string"),
+        "{}",
+        e
+    );
+
+    let rows = page.rows::<D>().unwrap();
+    assert!(matches!(&rows[0].a, Cow::Owned(s) if s == "a\"c"));
+}
+
+#[test]
+fn test_page_empty() {
+    let s = read("query_result_queued");
+    let page = Page::from_bytes(Bytes::from(s)).unwrap();
+    assert!(page.rows::<C>().unwrap().is_empty());
+}